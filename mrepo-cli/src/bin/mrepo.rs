@@ -3,8 +3,12 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
-use mrepo_cli::{Args, Commands};
-use mrepo_core::ContextWrapper;
+use chrono::DateTime;
+
+use mrepo_cli::{Args, Commands, ModuleCommands, SchemaKind, Table};
+use mrepo_core::model::config::{ModuleEntry, ModuleMetadata, ModuleSetting, RootSolution};
+use mrepo_core::model::schema;
+use mrepo_core::{Available, ContextWrapper, Init, ModuleInfo, Validate};
 
 #[inline]
 fn get_working_dir(args: &Args) -> Option<PathBuf> {
@@ -14,6 +18,110 @@ fn get_working_dir(args: &Args) -> Option<PathBuf> {
     }
 }
 
+#[inline]
+fn format_time(timestamp: i64) -> String {
+    match DateTime::from_timestamp_millis(timestamp) {
+        Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+#[inline]
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{size} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn print_info(info: &ModuleInfo) {
+    let config = &info.config;
+    println!("id:        {}", config.id);
+    if let Some(module) = &info.module {
+        println!("name:      {}", module.name);
+        println!("author:    {}", module.author);
+    }
+    println!("kind:      {}", config.kind.as_str());
+    println!("provider:  {}", config.provider);
+    if !config.changelog.is_empty() {
+        println!("changelog: {}", config.changelog);
+    }
+
+    let setting = &config.setting;
+    println!("disabled:  {}", setting.is_disabled());
+    if let Some(pin) = setting.pin_version_code {
+        println!("held at:   {pin}");
+    }
+    if let Some(keep_size) = setting.keep_size {
+        println!("keep size: {keep_size}");
+    }
+    if let Some(root) = &info.root {
+        let supported: Vec<&str> = RootSolution::ALL
+            .iter()
+            .filter(|s| root.get(**s))
+            .map(|s| s.as_str())
+            .collect();
+        println!("root:      {}", supported.join(", "));
+    }
+    println!("size:      {}", format_size(info.size()));
+
+    if info.versions.is_empty() {
+        println!("\nNo versions tracked");
+        return;
+    }
+
+    let file = |name: &str, size: Option<u64>| match size {
+        Some(s) => format!("{name} ({})", format_size(s)),
+        None if name.is_empty() => "-".to_owned(),
+        None => format!("{name} (missing)"),
+    };
+
+    let mut table = Table::new(vec![
+        "VERSION",
+        "CODE",
+        "TIMESTAMP",
+        "ZIP",
+        "CHANGELOG",
+        "FLAGS",
+    ]);
+    for v in &info.versions {
+        let mut flags = Vec::new();
+        if v.version.withdrawn {
+            flags.push("withdrawn");
+        }
+        if v.version.yanked {
+            flags.push("yanked");
+        }
+
+        table.push(vec![
+            v.version.version.to_owned(),
+            v.version.version_code.to_string(),
+            format_time(v.version.timestamp),
+            file(&v.version.zip_file, v.zip_size),
+            file(&v.version.changelog, v.changelog_size),
+            flags.join(","),
+        ]);
+    }
+    print!("\n{table}");
+}
+
+fn print_json<T: mrepo_core::util::Json>(value: &T) {
+    match value.to_string_pretty() {
+        Ok(s) => println!("{s}"),
+        Err(error) => eprintln!("Failed to serialize: {error}"),
+    }
+}
+
 #[cfg(feature = "git")]
 fn set_ssh_key(key: Option<String>) {
     use mrepo_core::constant;
@@ -47,10 +155,73 @@ async fn main() {
         }
     };
 
-    let context = match args.config {
-        Some(config_path) => ContextWrapper::build(config_path, working_dir),
-        None => ContextWrapper::from_working_dir(working_dir),
+    if let Commands::Init {
+        name,
+        base_url,
+        gitignore,
+        landing_page,
+    } = &args.command
+    {
+        let name = match name {
+            Some(n) => n.to_owned(),
+            None => working_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_owned(),
+        };
+
+        let _logger: Option<_> = if !args.quiet {
+            mrepo_log::init_tracing(&Default::default())
+        } else {
+            None
+        };
+
+        if Init::new(&working_dir).init(&name, base_url, *gitignore, *landing_page) {
+            println!("Initialized repository in {}", working_dir.display());
+        }
+        return;
+    }
+
+    if let Commands::Schema { kind } = args.command {
+        let schema = match kind {
+            SchemaKind::Config => schema::config(),
+            SchemaKind::Modules => schema::modules(),
+            SchemaKind::Track => schema::track(),
+        };
+
+        print_json(&schema);
+        return;
+    }
+
+    let validate = match &args.config {
+        Some(config_path) => Validate::new(config_path),
+        None => Validate::from_working_dir(&working_dir),
     };
+
+    if let Commands::Validate = args.command {
+        let config_path = validate.config_path();
+        let diagnostics = match validate.validate() {
+            Ok(d) => d,
+            Err(error) => {
+                eprintln!("Failed to read {}: {error}", config_path.display());
+                return;
+            }
+        };
+
+        for diagnostic in &diagnostics {
+            eprintln!("{}:{diagnostic}", diagnostic.file.display());
+        }
+
+        if diagnostics.is_empty() {
+            println!("{} is valid", config_path.display());
+        } else {
+            eprintln!("{} problems found", diagnostics.len());
+        }
+        return;
+    }
+
+    let context = ContextWrapper::build(validate.config_path(), &working_dir);
     let context = match context {
         Ok(c) => c,
         Err(error) => {
@@ -65,13 +236,30 @@ async fn main() {
         None
     };
 
+    let _lock = if args.command.need_lock() {
+        match context.lock(args.wait) {
+            Ok(l) => Some(l),
+            Err(error) => {
+                eprintln!("Failed to lock working directory: {error}");
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
     match args.command {
-        Commands::Format { write } => {
+        Commands::Init { .. } | Commands::Validate | Commands::Schema { .. } => {}
+        Commands::Format { write, migrate } => {
             let format = context.format();
-            match write {
-                Some(path) => format.write_to(path),
-                None => format.write(),
-            };
+            if migrate {
+                format.migrate();
+            } else {
+                match write {
+                    Some(path) => format.write_to(path),
+                    None => format.write(),
+                };
+            }
         }
         #[cfg(feature = "git")]
         Commands::Update { id, ssh_key } => {
@@ -93,5 +281,163 @@ async fn main() {
                 None => upgrade.generate_index(pretty).await,
             };
         }
+        Commands::Hold { id, version_code } => {
+            context.edit().hold(&id, version_code);
+        }
+        Commands::Unhold { id } => {
+            context.edit().unhold(&id);
+        }
+        Commands::Backfill { id, from } => {
+            context.backfill().backfill_id(&id, from.as_deref()).await;
+        }
+        Commands::Rollback { id, to } => {
+            context.rollback().rollback(&id, to);
+        }
+        Commands::Check { fix } => {
+            let issues = context.check().check_all(fix);
+            for issue in &issues {
+                println!("{issue}");
+            }
+
+            let fixed = issues.iter().filter(|i| i.fixed).count();
+            println!("{} issues found, {fixed} fixed", issues.len());
+        }
+        Commands::List { json } => {
+            let infos = context.info().list();
+            if json {
+                print_json(&infos);
+                return;
+            }
+
+            let mut table = Table::new(vec![
+                "ID", "KIND", "VERSION", "VERSIONS", "UPDATED", "SIZE", "STATE",
+            ]);
+            for info in infos {
+                let (version, updated) = match info.latest() {
+                    Some(v) => (
+                        v.version.version.to_owned(),
+                        format_time(v.version.timestamp),
+                    ),
+                    None => ("-".to_owned(), "-".to_owned()),
+                };
+                let setting = &info.config.setting;
+                let state = if setting.is_disabled() {
+                    "disabled"
+                } else if setting.pin_version_code.is_some() {
+                    "held"
+                } else if info.module.is_none() {
+                    "untracked"
+                } else {
+                    "-"
+                };
+
+                table.push(vec![
+                    info.config.id.to_owned(),
+                    info.config.kind.as_str().to_owned(),
+                    version,
+                    info.versions.len().to_string(),
+                    updated,
+                    format_size(info.size()),
+                    state.to_owned(),
+                ]);
+            }
+            print!("{table}");
+        }
+        Commands::Info { id, json } => match context.info().find(&id) {
+            Some(info) if json => print_json(&info),
+            Some(info) => print_info(&info),
+            None => eprintln!("Module `{id}` not found"),
+        },
+        Commands::Import { source, no_files } => {
+            context.import().import(&source, !no_files).await;
+        }
+        Commands::Module { command } => {
+            let edit = context.edit();
+            match command {
+                ModuleCommands::Add {
+                    id,
+                    template,
+                    kind,
+                    provider,
+                    changelog,
+                    license,
+                    homepage,
+                    source,
+                    donate,
+                    support,
+                    probe,
+                } => {
+                    let metadata = ModuleMetadata::new(license, homepage, donate, support, source);
+                    let entry = ModuleEntry {
+                        id,
+                        template,
+                        kind,
+                        provider,
+                        changelog,
+                        metadata,
+                        setting: ModuleSetting::default(),
+                    };
+                    edit.add(entry, probe).await;
+                }
+                ModuleCommands::Remove { id } => {
+                    edit.remove(&id);
+                }
+                ModuleCommands::Enable { id } => {
+                    edit.set_disabled(&id, false);
+                }
+                ModuleCommands::Disable { id } => {
+                    edit.set_disabled(&id, true);
+                }
+            };
+        }
+        Commands::Status { id } => {
+            let id = id.unwrap_or(Vec::new());
+            let statuses = context.status().status_all(&id).await;
+
+            let mut table = Table::new(vec![
+                "ID",
+                "KIND",
+                "CURRENT",
+                "AVAILABLE",
+                "UPDATED",
+                "STATE",
+            ]);
+            for status in statuses {
+                let (current, updated) = match &status.current {
+                    Some(v) => (v.version.to_owned(), format_time(v.timestamp)),
+                    None => ("-".to_owned(), "-".to_owned()),
+                };
+                let available = match &status.available {
+                    Available::Version(version, _) => version.to_owned(),
+                    Available::Release(tag, t) => format!("{tag} ({})", format_time(*t)),
+                    Available::Modified(t) => format!("modified {}", format_time(*t)),
+                    Available::Unknown => "-".to_owned(),
+                    Available::Failed => "error".to_owned(),
+                };
+
+                table.push(vec![
+                    status.id,
+                    status.kind.as_str().to_owned(),
+                    current,
+                    available,
+                    updated,
+                    status.state.as_str().to_owned(),
+                ]);
+            }
+            print!("{table}");
+        }
+        Commands::Yank {
+            id,
+            version_code,
+            reason,
+            undo,
+        } => {
+            let yank = context.yank();
+            if undo {
+                yank.unyank(&id, version_code);
+            } else {
+                yank.yank(&id, version_code, &reason);
+            }
+        }
     };
 }
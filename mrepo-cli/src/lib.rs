@@ -2,6 +2,12 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
+use mrepo_core::model::config::ProviderKind;
+
+pub use table::Table;
+
+mod table;
+
 #[derive(Parser, Debug)]
 #[command(version, disable_colored_help = true, disable_help_subcommand = true)]
 pub struct Args {
@@ -19,15 +25,44 @@ pub struct Args {
     /// Do not print log messages
     #[arg(long, default_value = "false", global = true)]
     pub quiet: bool,
+
+    /// Wait for another running instance instead of failing
+    #[arg(long, default_value = "false", global = true)]
+    pub wait: bool,
 }
 
 #[derive(clap::Subcommand, Debug)]
 pub enum Commands {
+    /// Create a new repository
+    Init {
+        /// Name of directory by default
+        #[arg(long, default_value = None)]
+        name: Option<String>,
+
+        #[arg(long, value_name = "URL", default_value = "")]
+        base_url: String,
+
+        /// Add .gitignore for temporary files
+        #[arg(long, default_value = "false")]
+        gitignore: bool,
+
+        /// Add a static landing page
+        #[arg(long, default_value = "false")]
+        landing_page: bool,
+    },
+
+    /// Validate configuration
+    Validate,
+
     /// Format configuration
     Format {
-        /// Write formatted config to file
+        /// Write formatted config to file, with includes inlined
         #[arg(short, long, value_name = "PATH", default_value = None)]
         write: Option<PathBuf>,
+
+        /// Upgrade config and track.json files to the current version
+        #[arg(long, default_value = "false", conflicts_with = "write")]
+        migrate: bool,
     },
 
     /// Update modules
@@ -52,4 +87,179 @@ pub enum Commands {
         #[arg(long, default_value = "false")]
         pretty: bool,
     },
+
+    /// Hold module at a version
+    Hold {
+        id: String,
+
+        /// Latest tracked by default
+        #[arg(long, value_name = "VERSION_CODE", default_value = None)]
+        version_code: Option<i64>,
+    },
+
+    /// Release held module
+    Unhold { id: String },
+
+    /// Fetch historical versions of a module
+    Backfill {
+        id: String,
+
+        /// modules.json URL, GitHub repository, directory of zips or git repository
+        #[arg(long, value_name = "SOURCE", default_value = None)]
+        from: Option<String>,
+    },
+
+    /// Republish a previous version as latest
+    Rollback {
+        id: String,
+
+        /// Previous retained version by default
+        #[arg(long, value_name = "VERSION_CODE", default_value = None)]
+        to: Option<i64>,
+    },
+
+    /// Check repository integrity
+    Check {
+        /// Repair what is safely repairable
+        #[arg(long, default_value = "false")]
+        fix: bool,
+    },
+
+    /// List tracked modules
+    List {
+        /// Print as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+
+    /// Show details of a module
+    Info {
+        id: String,
+
+        /// Print as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+
+    /// Edit modules in configuration
+    Module {
+        #[command(subcommand)]
+        command: ModuleCommands,
+    },
+
+    /// Add modules from an existing modules.json
+    Import {
+        /// Path or URL of modules.json
+        source: String,
+
+        /// Only add config entries, without downloading versions
+        #[arg(long, default_value = "false")]
+        no_files: bool,
+    },
+
+    /// Print JSON Schema
+    Schema {
+        #[arg(value_enum)]
+        kind: SchemaKind,
+    },
+
+    /// Show modules with pending updates
+    #[command(visible_alias = "outdated")]
+    Status {
+        /// All by default
+        #[arg(default_value = None)]
+        id: Option<Vec<String>>,
+    },
+
+    /// Mark a version as yanked
+    Yank {
+        id: String,
+
+        version_code: i64,
+
+        /// Reason shown to users
+        #[arg(short, long, value_name = "TEXT", default_value = "")]
+        reason: String,
+
+        /// Remove yanked mark
+        #[arg(long, default_value = "false")]
+        undo: bool,
+    },
+}
+
+#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+pub enum SchemaKind {
+    Config,
+    Modules,
+    Track,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ModuleCommands {
+    /// Add a module
+    Add {
+        #[arg(long)]
+        id: String,
+
+        /// Template to inherit kind, provider and metadata from
+        #[arg(long, default_value = "")]
+        template: String,
+
+        /// update-json, zip-url, mirror or git
+        #[arg(long)]
+        kind: Option<ProviderKind>,
+
+        #[arg(long, default_value = "")]
+        provider: String,
+
+        #[arg(long, default_value = "")]
+        changelog: String,
+
+        #[arg(long, default_value = "")]
+        license: String,
+
+        #[arg(long, default_value = "")]
+        homepage: String,
+
+        #[arg(long, default_value = "")]
+        source: String,
+
+        #[arg(long, default_value = "")]
+        donate: String,
+
+        #[arg(long, default_value = "")]
+        support: String,
+
+        /// Fill metadata from module.prop of the provider
+        #[arg(long, default_value = "false")]
+        probe: bool,
+    },
+
+    /// Remove a module
+    Remove { id: String },
+
+    /// Enable a module
+    Enable { id: String },
+
+    /// Disable a module
+    Disable { id: String },
+}
+
+impl Commands {
+    pub fn need_lock(&self) -> bool {
+        matches!(
+            self,
+            Commands::Format { .. }
+                | Commands::Update { .. }
+                | Commands::Upgrade { .. }
+                | Commands::Hold { .. }
+                | Commands::Unhold { .. }
+                | Commands::Rollback { .. }
+                | Commands::Backfill { .. }
+                | Commands::Yank { .. }
+                | Commands::Module { .. }
+                | Commands::Import { .. }
+                | Commands::Check { fix: true }
+        )
+    }
 }
@@ -0,0 +1,434 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use mrepo_model::config::{Module, ProviderKind, RepositorySetting};
+use mrepo_model::origin::{self, GithubRelease};
+use mrepo_model::track::{Track, Version};
+
+use crate::update::Update;
+use crate::util::{FileUtil, Json, LocalModule, Lock, Mirror, Request, StrUtil};
+use crate::{constant, Context};
+
+static GITHUB_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^https://(?:github\.com|raw\.githubusercontent\.com|api\.github\.com/repos)/([^/]+)/([^/#?]+)",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Source {
+    Index(String),
+    Github(String, String),
+    Dir(PathBuf),
+    #[cfg(feature = "git")]
+    Git(String),
+}
+
+impl Source {
+    pub(crate) fn github(url: &str) -> Option<Self> {
+        let captures = GITHUB_RE.captures(url)?;
+        let repo = captures[2].trim_end_matches(".git");
+        Some(Source::Github(captures[1].to_owned(), repo.to_owned()))
+    }
+
+    pub fn detect(module: &Module, from: Option<&str>) -> Option<Self> {
+        let from = match from {
+            Some(from) => from,
+            None => {
+                return match module.kind {
+                    ProviderKind::Mirror => Some(Source::Index(module.provider.to_owned())),
+                    #[cfg(feature = "git")]
+                    ProviderKind::Git => Some(Source::Git(module.provider.to_owned())),
+                    _ => Self::github(&module.provider),
+                }
+            }
+        };
+
+        if Path::new(from).is_dir() {
+            return Some(Source::Dir(PathBuf::from(from)));
+        }
+
+        #[cfg(feature = "git")]
+        if from.ends_with(".git") || from.starts_with("git@") {
+            return Some(Source::Git(from.to_owned()));
+        }
+
+        Self::github(from).or_else(|| Some(Source::Index(from.to_owned())))
+    }
+}
+
+pub struct Backfill {
+    update: Update,
+    modules_dir: PathBuf,
+}
+
+impl Backfill {
+    pub fn new<P: AsRef<Path>>(setting: &RepositorySetting, modules_dir: P) -> Self {
+        Self {
+            update: Update::new(setting, modules_dir.as_ref()),
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn accept(module: &Module, known: &mut Vec<i64>, prop: &origin::Module) -> bool {
+        if prop.id != module.id {
+            tracing::warn!(target: "Backfill::accept", id = %module.id, prop_id = %prop.id, "Id disagrees with module.prop");
+            return false;
+        }
+
+        if let Some(pin) = module.setting.pin_version_code {
+            if prop.version_code > pin {
+                return false;
+            }
+        }
+
+        if known.contains(&prop.version_code) {
+            return false;
+        }
+
+        known.push(prop.version_code);
+        true
+    }
+
+    fn stage_zip(
+        &self,
+        module: &Module,
+        zip_tmp: &Path,
+        timestamp: i64,
+        changelog: Option<Vec<u8>>,
+        known: &mut Vec<i64>,
+    ) -> Option<Version> {
+        let stage_dir = self.modules_dir.join(&module.id).join(constant::STAGE_DIR);
+        let prop = match LocalModule::read_zip(zip_tmp) {
+            Some(p) if Self::accept(module, known, &p) => p,
+            _ => {
+                FileUtil::remove(zip_tmp);
+                return None;
+            }
+        };
+
+        let version = StrUtil::get_version_display(&prop.version, prop.version_code);
+        let mut version = Version::new(timestamp, version, prop.version_code);
+        version.checksum = match FileUtil::sha256(zip_tmp) {
+            Some(c) => c,
+            None => {
+                FileUtil::remove(zip_tmp);
+                return None;
+            }
+        };
+
+        if !FileUtil::rename(zip_tmp, &stage_dir.join(&version.zip_file)) {
+            FileUtil::remove(zip_tmp);
+            return None;
+        }
+
+        let changelog_file = stage_dir.join(&version.changelog);
+        let written = changelog
+            .filter(|c| !c.is_empty())
+            .is_some_and(|c| fs::write(changelog_file, c).is_ok());
+        if !written {
+            version.changelog = String::new();
+        }
+
+        Some(version)
+    }
+
+    async fn stage_index(
+        &self,
+        module: &Module,
+        url: &str,
+        limit: usize,
+        known: &[i64],
+    ) -> Vec<Version> {
+        let upstream = match Mirror::load(url, &module.id).await {
+            Some(m) => m,
+            None => return Vec::new(),
+        };
+
+        let mut candidates: Vec<_> = upstream
+            .versions
+            .iter()
+            .filter(|v| !known.contains(&v.version_code))
+            .filter(|v| match module.setting.pin_version_code {
+                Some(pin) => v.version_code <= pin,
+                None => true,
+            })
+            .collect();
+        candidates.sort_by_key(|v| Reverse(v.version_code));
+
+        let mut staged = Vec::new();
+        for v in candidates.into_iter().take(limit) {
+            if let Some(version) = self.update.stage_mirror(module, v, None).await {
+                staged.push(version);
+            }
+        }
+
+        staged
+    }
+
+    async fn stage_github(
+        &self,
+        module: &Module,
+        owner: &str,
+        repo: &str,
+        limit: usize,
+        tracked: &[String],
+        known: &mut Vec<i64>,
+    ) -> Vec<Version> {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/releases?per_page=100");
+        let releases: Vec<GithubRelease> = match Request::github(&url).await {
+            Some(request) => request.json().await.unwrap_or_default(),
+            None => return Vec::new(),
+        };
+
+        let zip_tmp = self.modules_dir.join(&module.id).join(constant::TMP_FILE);
+        let mut staged = Vec::new();
+        for release in releases.iter().filter(|r| !r.draft && !r.prerelease) {
+            if staged.len() >= limit {
+                break;
+            }
+
+            if tracked
+                .iter()
+                .any(|v| StrUtil::matches_tag(v, &release.tag_name))
+            {
+                continue;
+            }
+
+            let mut assets = release.assets.iter().filter(|a| a.name.ends_with(".zip"));
+            let asset = match assets.clone().find(|a| a.name.contains(&module.id)) {
+                Some(a) => a,
+                None => match assets.next() {
+                    Some(a) => a,
+                    None => continue,
+                },
+            };
+
+            let timestamp = DateTime::parse_from_rfc3339(&release.published_at)
+                .map(|t| t.timestamp_millis())
+                .unwrap_or_else(|_| Utc::now().timestamp_millis());
+            if !Request::write_file(&asset.browser_download_url, &zip_tmp).await {
+                FileUtil::remove(&zip_tmp);
+                continue;
+            }
+
+            let changelog = Some(release.body.to_owned().into_bytes());
+            if let Some(version) = self.stage_zip(module, &zip_tmp, timestamp, changelog, known) {
+                staged.push(version);
+            }
+        }
+
+        staged
+    }
+
+    fn stage_local(
+        &self,
+        module: &Module,
+        dir: &Path,
+        limit: usize,
+        known: &mut Vec<i64>,
+    ) -> Vec<Version> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) => {
+                tracing::error!(target: "Backfill::stage_local", ?dir, ?error);
+                return Vec::new();
+            }
+        };
+
+        let mut zips: Vec<(PathBuf, i64)> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "zip"))
+            .filter_map(|p| {
+                let prop = LocalModule::read_zip(&p)?;
+                Some((p, prop.version_code))
+            })
+            .collect();
+        zips.sort_by_key(|(_, version_code)| Reverse(*version_code));
+
+        let zip_tmp = self.modules_dir.join(&module.id).join(constant::TMP_FILE);
+        let mut staged = Vec::new();
+        for (path, _) in zips {
+            if staged.len() >= limit {
+                break;
+            }
+
+            let timestamp = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|t| DateTime::<Utc>::from(t).timestamp_millis())
+                .unwrap_or_else(|_| Utc::now().timestamp_millis());
+            if let Some(parent) = zip_tmp.parent() {
+                fs::create_dir_all(parent).ok();
+            }
+            if fs::copy(&path, &zip_tmp).is_err() {
+                FileUtil::remove(&zip_tmp);
+                continue;
+            }
+
+            let changelog = fs::read(path.with_extension("md")).ok();
+            if let Some(version) = self.stage_zip(module, &zip_tmp, timestamp, changelog, known) {
+                staged.push(version);
+            }
+        }
+
+        staged
+    }
+
+    #[cfg(feature = "git")]
+    async fn stage_git(
+        &self,
+        module: &Module,
+        url: &str,
+        limit: usize,
+        known: &mut Vec<i64>,
+    ) -> Vec<Version> {
+        use crate::util::Git;
+
+        let module_dir = self.modules_dir.join(&module.id);
+        let dir_tmp = module_dir.join(constant::TMP_DIR);
+        let zip_tmp = module_dir.join(constant::TMP_FILE);
+        let repository = match Git::clone(url, &dir_tmp).await {
+            Some(r) => r,
+            None => {
+                FileUtil::remove(&dir_tmp);
+                return Vec::new();
+            }
+        };
+
+        let mut staged = Vec::new();
+        for (tag, time) in Git::tags(&repository) {
+            if staged.len() >= limit {
+                break;
+            }
+
+            if !Git::checkout(&repository, &tag)
+                || LocalModule::from_zip(&dir_tmp, &zip_tmp).is_none()
+            {
+                FileUtil::remove(&zip_tmp);
+                continue;
+            }
+
+            let timestamp = time.timestamp_millis();
+            if let Some(version) = self.stage_zip(module, &zip_tmp, timestamp, None, known) {
+                staged.push(version);
+            }
+        }
+
+        FileUtil::remove(&dir_tmp);
+        staged
+    }
+
+    pub async fn backfill(&self, module: &Module, from: Option<&str>) -> bool {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        let source = match Source::detect(module, from) {
+            Some(s) => s,
+            None => {
+                tracing::error!(target: "Backfill::backfill", id = %module.id, "No history source found, use --from");
+                return false;
+            }
+        };
+
+        tracing::info!(target: "Backfill::backfill", id = %module.id, ?source);
+        let module_dir = self.modules_dir.join(&module.id);
+        FileUtil::remove(module_dir.join(constant::STAGE_DIR));
+
+        let track = Track::from_file(module_dir.join(constant::TRACK_JSON)).ok();
+        let mut known: Vec<i64> = match &track {
+            Some(t) => t.versions.iter().map(|v| v.version_code).collect(),
+            None => Vec::new(),
+        };
+
+        let counted = match &track {
+            Some(t) => t
+                .versions
+                .iter()
+                .filter(|v| !v.yanked && !v.withdrawn)
+                .count(),
+            None => 0,
+        };
+        let limit = self.update.keep_size(module).saturating_sub(counted);
+        if limit == 0 {
+            tracing::info!(target: "Backfill::backfill", id = %module.id, "Already at keep_size");
+            return false;
+        }
+
+        let staged = match source {
+            Source::Index(url) => self.stage_index(module, &url, limit, &known).await,
+            Source::Github(owner, repo) => {
+                let tracked: Vec<String> = match &track {
+                    Some(t) => t.versions.iter().map(|v| v.version.to_owned()).collect(),
+                    None => Vec::new(),
+                };
+                self.stage_github(module, &owner, &repo, limit, &tracked, &mut known)
+                    .await
+            }
+            Source::Dir(dir) => self.stage_local(module, &dir, limit, &mut known),
+            #[cfg(feature = "git")]
+            Source::Git(url) => self.stage_git(module, &url, limit, &mut known).await,
+        };
+
+        if staged.is_empty() {
+            tracing::info!(target: "Backfill::backfill", id = %module.id, "No new versions found");
+            FileUtil::remove(module_dir.join(constant::STAGE_DIR));
+            return false;
+        }
+
+        for version in &staged {
+            tracing::info!(target: "Backfill::backfill", id = %module.id, version = %version.version, timestamp = %version.timestamp);
+        }
+
+        let (track_module, versions) = match track {
+            Some(t) => (Some(t.module), t.versions),
+            None => (None, Vec::new()),
+        };
+        self.update
+            .merge_staged(module, track_module, versions, staged)
+    }
+}
+
+pub struct BackfillWrapper<'b> {
+    modules: &'b Vec<Arc<Module>>,
+    original: Arc<Backfill>,
+}
+
+impl<'b> BackfillWrapper<'b> {
+    pub fn build(context: &'b Context) -> Self {
+        let backfill = Backfill::new(&context.repository.setting, &context.modules_dir);
+
+        Self {
+            modules: &context.modules,
+            original: Arc::new(backfill),
+        }
+    }
+
+    pub async fn backfill_id(&self, id: &str, from: Option<&str>) -> bool {
+        match self.modules.iter().find(|m| m.id == id) {
+            Some(module) => self.original.backfill(module, from).await,
+            None => {
+                tracing::error!(target: "Backfill::backfill", %id, "Module not found");
+                false
+            }
+        }
+    }
+}
+
+impl Deref for BackfillWrapper<'_> {
+    type Target = Backfill;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
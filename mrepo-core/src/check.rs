@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use walkdir::WalkDir;
+
+use mrepo_model::config::Module;
+use mrepo_model::origin;
+use mrepo_model::track::Track;
+
+use crate::util::{FileUtil, Json, LocalModule, Lock};
+use crate::{constant, Context};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum IssueKind {
+    MissingTrack,
+    UnparsableTrack(String),
+    MissingZip(i64, String),
+    MissingChangelog(i64, String),
+    MissingChecksum(i64),
+    ChecksumMismatch(i64, String),
+    PropMismatch(i64, &'static str),
+    ZipMismatch(i64, &'static str),
+    OrphanFile(PathBuf),
+    UnknownModule(PathBuf),
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::MissingTrack => write!(f, "track.json not found"),
+            IssueKind::UnparsableTrack(error) => write!(f, "track.json unparsable: {error}"),
+            IssueKind::MissingZip(code, file) => write!(f, "{code}: zip `{file}` not found"),
+            IssueKind::MissingChangelog(code, file) => {
+                write!(f, "{code}: changelog `{file}` not found")
+            }
+            IssueKind::MissingChecksum(code) => write!(f, "{code}: checksum not recorded"),
+            IssueKind::ChecksumMismatch(code, actual) => {
+                write!(f, "{code}: checksum mismatch (actual {actual})")
+            }
+            IssueKind::PropMismatch(code, field) => {
+                write!(f, "{code}: module.prop `{field}` disagrees with track")
+            }
+            IssueKind::ZipMismatch(code, field) => {
+                write!(f, "{code}: module.prop `{field}` disagrees with version")
+            }
+            IssueKind::OrphanFile(path) => write!(f, "orphan file {}", path.display()),
+            IssueKind::UnknownModule(path) => write!(f, "unknown module {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Issue {
+    pub id: String,
+    pub kind: IssueKind,
+    pub fixed: bool,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fixed {
+            write!(f, "{}: {} (fixed)", self.id, self.kind)
+        } else {
+            write!(f, "{}: {}", self.id, self.kind)
+        }
+    }
+}
+
+pub struct Check {
+    modules_dir: PathBuf,
+}
+
+impl Check {
+    pub fn new<P: AsRef<Path>>(modules_dir: P) -> Self {
+        Self {
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn prop_mismatches(track: &origin::Module, zip: &origin::Module) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if track.id != zip.id {
+            fields.push("id");
+        }
+        if track.name != zip.name {
+            fields.push("name");
+        }
+        if track.version != zip.version {
+            fields.push("version");
+        }
+        if track.version_code != zip.version_code {
+            fields.push("versionCode");
+        }
+        if track.author != zip.author {
+            fields.push("author");
+        }
+        if track.description != zip.description {
+            fields.push("description");
+        }
+        // Tracks written before module.prop keys were kept have none, that is unknown.
+        if !track.prop.is_empty() && track.prop != zip.prop {
+            fields.push("prop");
+        }
+
+        fields
+    }
+
+    fn check_track(&self, id: &str, track: &mut Track, fix: bool) -> Vec<IssueKind> {
+        let module_dir = self.modules_dir.join(id);
+        let mut issues = Vec::new();
+        let mut module_new = None;
+
+        track.versions.retain_mut(|v| {
+            let zip_file = module_dir.join(&v.zip_file);
+            if !zip_file.is_file() {
+                issues.push(IssueKind::MissingZip(v.version_code, v.zip_file.to_owned()));
+                return !fix;
+            }
+
+            if !v.changelog.is_empty() && !module_dir.join(&v.changelog).is_file() {
+                issues.push(IssueKind::MissingChangelog(
+                    v.version_code,
+                    v.changelog.to_owned(),
+                ));
+                if fix {
+                    v.changelog = String::new();
+                }
+            }
+
+            let checksum = FileUtil::sha256(&zip_file).unwrap_or_default();
+            if v.checksum.is_empty() {
+                issues.push(IssueKind::MissingChecksum(v.version_code));
+                if fix {
+                    v.checksum = checksum;
+                }
+            } else if v.checksum != checksum {
+                issues.push(IssueKind::ChecksumMismatch(v.version_code, checksum));
+            }
+
+            if let Some(zip) = LocalModule::read_zip(&zip_file) {
+                if zip.id != id {
+                    issues.push(IssueKind::ZipMismatch(v.version_code, "id"));
+                } else if zip.version_code != v.version_code {
+                    issues.push(IssueKind::ZipMismatch(v.version_code, "versionCode"));
+                } else if v.version_code == track.module.version_code {
+                    Self::prop_mismatches(&track.module, &zip)
+                        .into_iter()
+                        .for_each(|f| issues.push(IssueKind::PropMismatch(v.version_code, f)));
+                    module_new = Some(zip);
+                }
+            }
+
+            true
+        });
+
+        if let Some(zip) = module_new.filter(|_| fix) {
+            track.module = zip;
+        }
+
+        issues
+    }
+
+    fn check_files(&self, id: &str, track: &Track, fix: bool) -> Vec<IssueKind> {
+        let module_dir = self.modules_dir.join(id);
+        let mut known: HashSet<&str> = HashSet::new();
+        known.insert(constant::TRACK_JSON);
+        for v in &track.versions {
+            known.insert(&v.zip_file);
+            known.insert(&v.changelog);
+        }
+
+        let track_bak = format!("{}.{}", constant::TRACK_JSON, constant::BAK_EXT);
+        let walk_dir = WalkDir::new(&module_dir).min_depth(1).max_depth(1);
+
+        let mut issues = Vec::new();
+        for entry in walk_dir.into_iter().filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_str().unwrap_or("");
+            if known.contains(name) || name == track_bak {
+                continue;
+            }
+
+            if fix {
+                FileUtil::remove(entry.path());
+            }
+            issues.push(IssueKind::OrphanFile(entry.into_path()));
+        }
+
+        issues
+    }
+
+    pub fn check(&self, module: &Module, fix: bool) -> Vec<Issue> {
+        let lock = fix.then(|| Lock::hold(&self.modules_dir)).flatten();
+        self.check_locked(module, fix && lock.is_some())
+    }
+
+    fn check_locked(&self, module: &Module, fix: bool) -> Vec<Issue> {
+        let module_dir = self.modules_dir.join(&module.id);
+        let track_json = module_dir.join(constant::TRACK_JSON);
+        let issue = |kind, fixed| Issue {
+            id: module.id.to_owned(),
+            kind,
+            fixed,
+        };
+
+        if !track_json.exists() {
+            return vec![issue(IssueKind::MissingTrack, false)];
+        }
+
+        let mut kinds = Vec::new();
+        let mut recovered = false;
+        if let Err(error) = fs::read(&track_json)
+            .map_err(|e| e.to_string())
+            .and_then(|v| serde_json::from_slice::<Track>(&v).map_err(|e| e.to_string()))
+        {
+            kinds.push(IssueKind::UnparsableTrack(error));
+            recovered = true;
+        }
+
+        let mut track = match Track::from_file(&track_json) {
+            Ok(t) => t,
+            Err(_) => {
+                return kinds.into_iter().map(|k| issue(k, false)).collect();
+            }
+        };
+
+        let origin = track.to_owned();
+        kinds.extend(self.check_track(&module.id, &mut track, fix));
+        kinds.extend(self.check_files(&module.id, &track, fix));
+
+        let mut fixed = false;
+        if fix && (recovered || track != origin) {
+            match track.to_file(&track_json, true) {
+                Ok(_) => fixed = true,
+                Err(error) => {
+                    tracing::error!(target: "Check::check", id = %module.id, ?error);
+                }
+            }
+        }
+
+        kinds
+            .into_iter()
+            .map(|k| {
+                let is_fixed = match &k {
+                    IssueKind::OrphanFile(path) => fix && !path.exists(),
+                    IssueKind::UnparsableTrack(_)
+                    | IssueKind::MissingZip(..)
+                    | IssueKind::MissingChangelog(..)
+                    | IssueKind::MissingChecksum(_)
+                    | IssueKind::PropMismatch(..) => fixed,
+                    _ => false,
+                };
+
+                issue(k, is_fixed)
+            })
+            .collect()
+    }
+
+    pub fn check_unknown(&self, modules: &[Arc<Module>], fix: bool) -> Vec<Issue> {
+        let lock = fix.then(|| Lock::hold(&self.modules_dir)).flatten();
+        self.check_unknown_locked(modules, fix && lock.is_some())
+    }
+
+    fn check_unknown_locked(&self, modules: &[Arc<Module>], fix: bool) -> Vec<Issue> {
+        let module_ids: HashSet<&str> = modules.iter().map(|m| m.id.as_str()).collect();
+        let walk_dir = WalkDir::new(&self.modules_dir).min_depth(1).max_depth(1);
+
+        let mut issues = Vec::new();
+        for entry in walk_dir.into_iter().filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_str().unwrap_or("").to_owned();
+            if entry.path().is_dir() && module_ids.contains(name.as_str()) {
+                continue;
+            }
+
+            let fixed = fix && FileUtil::remove(entry.path());
+            issues.push(Issue {
+                id: name,
+                kind: IssueKind::UnknownModule(entry.into_path()),
+                fixed,
+            });
+        }
+
+        issues
+    }
+}
+
+pub struct CheckWrapper<'c> {
+    modules: &'c Vec<Arc<Module>>,
+    original: Arc<Check>,
+}
+
+impl<'c> CheckWrapper<'c> {
+    pub fn build(context: &'c Context) -> Self {
+        let check = Check::new(&context.modules_dir);
+
+        Self {
+            modules: &context.modules,
+            original: Arc::new(check),
+        }
+    }
+
+    pub fn check_all(&self, fix: bool) -> Vec<Issue> {
+        let lock = fix.then(|| Lock::hold(&self.modules_dir)).flatten();
+        let fix = fix && lock.is_some();
+
+        let mut issues: Vec<Issue> = self
+            .modules
+            .iter()
+            .flat_map(|m| self.check_locked(m, fix))
+            .collect();
+
+        issues.extend(self.check_unknown_locked(self.modules, fix));
+        issues
+    }
+}
+
+impl Deref for CheckWrapper<'_> {
+    type Target = Check;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
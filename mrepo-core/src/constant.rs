@@ -1,14 +1,21 @@
 #![allow(unused)]
 
+pub const CONFIG_NAME: &str = "config";
 pub const CONFIG_JSON: &str = "config.json";
+pub const CONFIG_EXAMPLE: &str = "config.example.toml";
 pub const MODULES_JSON: &str = "modules.json";
 pub const TRACK_JSON: &str = "track.json";
 pub const JSON_DIR: &str = "json";
 pub const MODULES_DIR: &str = "modules";
+pub const LOCK_FILE: &str = ".lock";
+pub const GITHUB_TOKEN: &str = "GITHUB_TOKEN";
 
 pub(crate) const MODULE_PROP: &str = "module.prop";
 pub(crate) const TMP_FILE: &str = "tmp";
 pub(crate) const TMP_DIR: &str = "tmp.d";
+pub(crate) const STAGE_DIR: &str = "stage.d";
+pub(crate) const TMP_EXT: &str = "tmp";
+pub(crate) const BAK_EXT: &str = "bak";
 
 #[cfg(feature = "git")]
 pub const SSH_PRIVATE_KEY: &str = "SSH_PRIVATE_KEY";
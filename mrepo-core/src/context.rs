@@ -1,10 +1,10 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use mrepo_model::config::{Config, Log, Module, Repository};
+use mrepo_model::config::{Log, Module, Repository};
 
 use crate::error;
-use crate::util::Json;
+use crate::util::{ConfigFormat, ConfigSet, Templates};
 
 pub struct Context {
     pub log: Log,
@@ -17,12 +17,14 @@ pub struct Context {
 
 impl Context {
     pub fn new<P: AsRef<Path>>(config_path: P, json_dir: P, modules_dir: P) -> error::Result<Self> {
-        let config = Config::from_file(&config_path)?;
+        let set = ConfigSet::read(&config_path)?;
+        let config = ConfigFormat::expand_env(&set.flatten()?)?;
+        let modules = Templates::from_config(&config).resolve_all(&config.modules)?;
 
         Ok(Self {
             log: config.log,
             repository: config.repository,
-            modules: config.modules.into_iter().map(Arc::new).collect(),
+            modules: modules.into_iter().map(Arc::new).collect(),
             config_path: PathBuf::from(config_path.as_ref()),
             json_dir: PathBuf::from(json_dir.as_ref()),
             modules_dir: PathBuf::from(modules_dir.as_ref()),
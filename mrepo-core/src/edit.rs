@@ -0,0 +1,271 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mrepo_model::config::{Module, ModuleEntry, ModuleMetadata, ProviderKind};
+use mrepo_model::origin::UpdateJson;
+use mrepo_model::track::Track;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::util::{
+    ConfigFormat, ConfigSet, FileUtil, Json, LocalModule, Lock, Mirror, Request, Templates,
+};
+use crate::{constant, error};
+
+pub struct Edit {
+    config_path: PathBuf,
+    modules_dir: PathBuf,
+}
+
+impl Edit {
+    pub fn new<P: AsRef<Path>>(config_path: P, modules_dir: P) -> Self {
+        Self {
+            config_path: PathBuf::from(config_path.as_ref()),
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn modify_config<F>(&self, f: F) -> error::Result<()>
+    where
+        F: FnOnce(&mut ConfigSet) -> error::Result<()>,
+    {
+        let _lock = Lock::acquire(Lock::path(&self.modules_dir), false)?;
+        let mut set = ConfigSet::read(&self.config_path)?;
+        f(&mut set)?;
+        set.write()
+    }
+
+    fn modify<F>(&self, id: &str, f: F) -> error::Result<()>
+    where
+        F: FnOnce(&mut ModuleEntry) -> error::Result<()>,
+    {
+        self.modify_config(|set| match set.find_mut(id) {
+            Some(m) => f(m),
+            None => Err(Error::custom(format!("Module `{id}` not found"))),
+        })
+    }
+
+    fn resolve(&self, entry: &ModuleEntry) -> error::Result<Module> {
+        let set = ConfigSet::read(&self.config_path)?;
+        let module = Templates::from_config(&set.config)
+            .resolve(entry)
+            .map_err(Error::custom)?;
+
+        ConfigFormat::expand_env(&module)
+    }
+
+    async fn probe(&self, module: &Module) -> Option<ModuleMetadata> {
+        let zip_url = match module.kind {
+            ProviderKind::UpdateJson => {
+                Request::load_json::<UpdateJson>(&module.provider)
+                    .await?
+                    .zip_url
+            }
+            ProviderKind::ZipUrl => module.provider.to_owned(),
+            ProviderKind::Mirror => {
+                let upstream = Mirror::load(&module.provider, &module.id).await?;
+                Mirror::latest(&upstream)?.zip_url.to_owned()
+            }
+            #[cfg(feature = "git")]
+            ProviderKind::Git => {
+                tracing::warn!(target: "Edit::probe", id = %module.id, "Probing git provider is not supported");
+                return None;
+            }
+        };
+
+        let module_dir = self.modules_dir.join(&module.id);
+        let created = !module_dir.exists();
+        let zip_tmp = module_dir.join(constant::TMP_FILE);
+
+        let (prop, metadata) = if Request::write_file(&zip_url, &zip_tmp).await {
+            (
+                LocalModule::read_zip(&zip_tmp),
+                LocalModule::read_zip_as::<ModuleMetadata, _>(&zip_tmp),
+            )
+        } else {
+            (None, None)
+        };
+
+        FileUtil::remove(&zip_tmp);
+        if created {
+            fs::remove_dir(&module_dir).ok();
+        }
+
+        if let Some(prop) = prop.filter(|p| p.id != module.id) {
+            tracing::warn!(target: "Edit::probe", id = %module.id, prop_id = %prop.id, "Id disagrees with module.prop");
+        }
+
+        metadata
+    }
+
+    pub async fn add(&self, mut entry: ModuleEntry, probe: bool) -> bool {
+        let id = entry.id.to_owned();
+        let module = match self.resolve(&entry) {
+            Ok(m) => m,
+            Err(error) => {
+                tracing::error!(target: "Edit::add", %id, ?error);
+                return false;
+            }
+        };
+
+        if probe {
+            match self.probe(&module).await {
+                Some(found) => {
+                    let metadata = &mut entry.metadata;
+                    let resolved = &module.metadata;
+                    for (field, inherited, value) in [
+                        (&mut metadata.license, &resolved.license, found.license),
+                        (&mut metadata.homepage, &resolved.homepage, found.homepage),
+                        (&mut metadata.source, &resolved.source, found.source),
+                        (&mut metadata.donate, &resolved.donate, found.donate),
+                        (&mut metadata.support, &resolved.support, found.support),
+                    ] {
+                        if inherited.is_empty() {
+                            *field = value;
+                        }
+                    }
+                }
+                None => {
+                    tracing::error!(target: "Edit::add", %id, "Failed to probe provider");
+                    return false;
+                }
+            }
+        }
+
+        let result = self.modify_config(|set| {
+            if set.contains(&id) {
+                return Err(Error::custom(format!("Module `{id}` already exists")));
+            }
+
+            set.config.modules.push(entry);
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                tracing::info!(target: "Edit::add", %id);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Edit::add", %id, ?error);
+                false
+            }
+        }
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        let result = self.modify_config(|set| {
+            if !set.remove(id) {
+                return Err(Error::custom(format!("Module `{id}` not found")));
+            }
+
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                tracing::info!(target: "Edit::remove", %id);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Edit::remove", %id, ?error);
+                false
+            }
+        }
+    }
+
+    pub fn set_disabled(&self, id: &str, disabled: bool) -> bool {
+        let result = self.modify(id, |m| {
+            m.setting.disabled = Some(disabled);
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                tracing::info!(target: "Edit::set_disabled", %id, %disabled);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Edit::set_disabled", %id, ?error);
+                false
+            }
+        }
+    }
+
+    fn latest_version_code(&self, id: &str) -> error::Result<i64> {
+        let track_json = self.modules_dir.join(id).join(constant::TRACK_JSON);
+        let track = Track::from_file(track_json)?;
+
+        match track.versions.iter().find(|v| !v.withdrawn) {
+            Some(v) => Ok(v.version_code),
+            None => Ok(track.module.version_code),
+        }
+    }
+
+    pub fn hold(&self, id: &str, version_code: Option<i64>) -> bool {
+        let mut pin = None;
+        let result = self.modify(id, |m| {
+            let version_code = match version_code {
+                Some(v) => v,
+                None => self.latest_version_code(&m.id)?,
+            };
+
+            m.setting.pin_version_code = Some(version_code);
+            pin = Some(version_code);
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                tracing::info!(target: "Edit::hold", %id, version_code = ?pin);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Edit::hold", %id, ?error);
+                false
+            }
+        }
+    }
+
+    pub fn unhold(&self, id: &str) -> bool {
+        let result = self.modify(id, |m| {
+            m.setting.pin_version_code = None;
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                tracing::info!(target: "Edit::unhold", %id);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Edit::unhold", %id, ?error);
+                false
+            }
+        }
+    }
+}
+
+pub struct EditWrapper {
+    original: Arc<Edit>,
+}
+
+impl EditWrapper {
+    pub fn build(context: &Context) -> Self {
+        let edit = Edit::new(&context.config_path, &context.modules_dir);
+
+        Self {
+            original: Arc::new(edit),
+        }
+    }
+}
+
+impl Deref for EditWrapper {
+    type Target = Edit;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
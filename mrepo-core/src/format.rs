@@ -1,31 +1,60 @@
+use std::fs;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use mrepo_model::config::Config;
+use mrepo_model::migrate;
+use mrepo_model::track::Track;
 
 use crate::context::Context;
-use crate::util::Json;
+use crate::util::{ConfigFormat, ConfigSet, Json, Lock};
+use crate::{constant, error};
 
 pub struct Format {
     config_path: PathBuf,
+    modules_dir: PathBuf,
 }
 
 impl Format {
-    pub fn new<P: AsRef<Path>>(config_path: P) -> Self {
+    pub fn new<P: AsRef<Path>>(config_path: P, modules_dir: P) -> Self {
         Self {
             config_path: PathBuf::from(config_path.as_ref()),
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
         }
     }
 
+    pub fn write_config<P: AsRef<Path>>(config: &Config, path: P) -> error::Result<()> {
+        ConfigFormat::write(config, path)
+    }
+
     pub fn write_to<P: AsRef<Path>>(&self, path: P) -> bool {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        self.write_locked(path.as_ref())
+    }
+
+    fn write_locked(&self, path: &Path) -> bool {
         fn inner(from: &PathBuf, to: &Path) -> anyhow::Result<()> {
-            let config = Config::from_file(from)?;
-            config.to_file(to, true)?;
+            let set = ConfigSet::read(from)?;
+            let same = match (fs::canonicalize(from), fs::canonicalize(to)) {
+                (Ok(from), Ok(to)) => from == to,
+                _ => false,
+            };
+
+            if same {
+                set.write_all()?;
+            } else {
+                Format::write_config(&set.flatten()?, to)?;
+            }
             Ok(())
         }
 
-        let path = path.as_ref();
         match inner(&self.config_path, path) {
             Ok(_) => true,
             Err(error) => {
@@ -34,6 +63,51 @@ impl Format {
             }
         }
     }
+
+    pub fn migrate(&self) -> bool {
+        fn inner(track_json: &Path) -> anyhow::Result<bool> {
+            let mut value: Value = serde_json::from_slice(&fs::read(track_json)?)?;
+            if !migrate::track(&mut value).map_err(anyhow::Error::msg)? {
+                return Ok(false);
+            }
+
+            let track: Track = serde_json::from_value(value)?;
+            track.to_file(track_json, true)?;
+            Ok(true)
+        }
+
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        let mut ok = self.write_locked(&self.config_path);
+        let entries = match fs::read_dir(&self.modules_dir) {
+            Ok(entries) => entries,
+            Err(_) => return ok,
+        };
+
+        let mut dirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        dirs.sort();
+        for track_json in dirs.iter().map(|d| d.join(constant::TRACK_JSON)) {
+            if !track_json.is_file() {
+                continue;
+            }
+
+            match inner(&track_json) {
+                Ok(true) => {
+                    tracing::info!(target: "Format::migrate", path = ?track_json, version = migrate::TRACK_VERSION);
+                }
+                Ok(false) => {}
+                Err(error) => {
+                    tracing::error!(target: "Format::migrate", path = ?track_json, ?error);
+                    ok = false;
+                }
+            }
+        }
+
+        ok
+    }
 }
 
 pub struct FormatWrapper {
@@ -42,7 +116,7 @@ pub struct FormatWrapper {
 
 impl FormatWrapper {
     pub fn build(context: &Context) -> Self {
-        let format = Format::new(&context.config_path);
+        let format = Format::new(&context.config_path, &context.modules_dir);
 
         Self {
             original: Arc::new(format),
@@ -0,0 +1,172 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mrepo_model::config::{Module, ModuleSetting, ProviderKind, RepositorySetting};
+use mrepo_model::modules::{self, Modules};
+use mrepo_model::track::Track;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::update::Update;
+use crate::util::{ConfigSet, Json, Lock, Request};
+use crate::{constant, error};
+
+pub struct Import {
+    update: Update,
+    config_path: PathBuf,
+    modules_dir: PathBuf,
+}
+
+impl Import {
+    pub fn new<P: AsRef<Path>>(
+        setting: &RepositorySetting,
+        config_path: P,
+        modules_dir: P,
+    ) -> Self {
+        Self {
+            update: Update::new(setting, modules_dir.as_ref()),
+            config_path: PathBuf::from(config_path.as_ref()),
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    async fn load(source: &str) -> error::Result<Modules> {
+        if Self::is_remote(source) {
+            Request::load_json(source)
+                .await
+                .ok_or_else(|| Error::custom(format!("Failed to load `{source}`")))
+        } else {
+            Modules::from_file(source)
+        }
+    }
+
+    fn is_remote(source: &str) -> bool {
+        source.starts_with("http://") || source.starts_with("https://")
+    }
+
+    async fn seed(&self, entry: &Module, module: &modules::Module) -> error::Result<usize> {
+        let track_json = self.modules_dir.join(&module.id).join(constant::TRACK_JSON);
+        if track_json.exists() {
+            return Err(Error::custom(format!(
+                "{} already exists",
+                track_json.display()
+            )));
+        }
+
+        if module.versions.is_empty() {
+            return Ok(0);
+        }
+
+        if !self.update.update_by_upstream(entry, module).await {
+            return Err(Error::custom("Failed to download versions"));
+        }
+
+        Ok(Track::from_file(&track_json)?.versions.len())
+    }
+
+    fn entry(module: &modules::Module, source: &str) -> Module {
+        // A local index can't be followed, the latest zip is tracked instead.
+        let (kind, provider) = if Self::is_remote(source) {
+            (ProviderKind::Mirror, source.to_owned())
+        } else {
+            let zip_url = module
+                .versions
+                .first()
+                .map(|v| v.zip_url.to_owned())
+                .unwrap_or_default();
+            (ProviderKind::ZipUrl, zip_url)
+        };
+
+        Module::new(
+            module.id.to_owned(),
+            kind,
+            provider,
+            String::new(),
+            module.metadata.to_owned(),
+            ModuleSetting::default(),
+        )
+    }
+
+    pub async fn import(&self, source: &str, with_files: bool) -> bool {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        let index = match Self::load(source).await {
+            Ok(index) => index,
+            Err(error) => {
+                tracing::error!(target: "Import::import", %source, ?error);
+                return false;
+            }
+        };
+
+        let mut set = match ConfigSet::read(&self.config_path) {
+            Ok(set) => set,
+            Err(error) => {
+                tracing::error!(target: "Import::import", %source, ?error);
+                return false;
+            }
+        };
+
+        let mut is_ok = true;
+        for module in &index.modules {
+            let id = &module.id;
+            if set.contains(id) {
+                tracing::warn!(target: "Import::import", %id, "Module already exists");
+                continue;
+            }
+
+            let entry = Self::entry(module, source);
+            if with_files {
+                match self.seed(&entry, module).await {
+                    Ok(count) => {
+                        tracing::info!(target: "Import::import", %id, versions = %count, total = %module.versions.len());
+                    }
+                    Err(error) => {
+                        tracing::error!(target: "Import::import", %id, ?error);
+                        is_ok = false;
+                    }
+                }
+            } else {
+                tracing::info!(target: "Import::import", %id);
+            }
+
+            set.config.modules.push(entry.into());
+        }
+
+        if let Err(error) = set.write() {
+            tracing::error!(target: "Import::import", %source, ?error);
+            return false;
+        }
+
+        is_ok
+    }
+}
+
+pub struct ImportWrapper {
+    original: Arc<Import>,
+}
+
+impl ImportWrapper {
+    pub fn build(context: &Context) -> Self {
+        let import = Import::new(
+            &context.repository.setting,
+            &context.config_path,
+            &context.modules_dir,
+        );
+
+        Self {
+            original: Arc::new(import),
+        }
+    }
+}
+
+impl Deref for ImportWrapper {
+    type Target = Import;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
@@ -0,0 +1,144 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use mrepo_model::config::{Module, RootSupport};
+use mrepo_model::origin;
+use mrepo_model::track::{Track, Version};
+
+use crate::util::{FileUtil, Json, Root};
+use crate::{constant, Context};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ModuleInfo {
+    #[serde(flatten)]
+    pub config: Module,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<origin::Module>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<RootSupport>,
+    pub versions: Vec<VersionInfo>,
+}
+
+impl ModuleInfo {
+    pub fn latest(&self) -> Option<&VersionInfo> {
+        self.versions.iter().find(|v| !v.version.withdrawn)
+    }
+
+    pub fn size(&self) -> u64 {
+        self.versions
+            .iter()
+            .map(|v| v.zip_size.unwrap_or(0) + v.changelog_size.unwrap_or(0))
+            .sum()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VersionInfo {
+    #[serde(flatten)]
+    pub version: Version,
+    pub zip_size: Option<u64>,
+    pub changelog_size: Option<u64>,
+}
+
+pub struct Info {
+    modules_dir: PathBuf,
+}
+
+impl Info {
+    pub fn new<P: AsRef<Path>>(modules_dir: P) -> Self {
+        Self {
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn file_size(&self, id: &str, file: &str) -> Option<u64> {
+        let path = self.modules_dir.join(id).join(file);
+        if !file.is_empty() && path.is_file() {
+            Some(FileUtil::size(path))
+        } else {
+            None
+        }
+    }
+
+    pub fn info(&self, module: &Module) -> ModuleInfo {
+        let track_json = self.modules_dir.join(&module.id).join(constant::TRACK_JSON);
+        let track = if track_json.exists() {
+            match Track::from_file(&track_json) {
+                Ok(t) => Some(t),
+                Err(error) => {
+                    tracing::error!(target: "Info::info", id = %module.id, ?error);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let (origin, versions) = match track {
+            Some(t) => (Some(t.module), t.versions),
+            None => (None, Vec::new()),
+        };
+
+        let latest = versions.iter().find(|v| !v.withdrawn);
+        let root = latest
+            .and_then(|v| Root::detect(self.modules_dir.join(&module.id).join(&v.zip_file)))
+            .map(|mut r| {
+                r.apply(&module.setting.root);
+                r
+            });
+
+        let versions = versions
+            .into_iter()
+            .map(|v| VersionInfo {
+                zip_size: self.file_size(&module.id, &v.zip_file),
+                changelog_size: self.file_size(&module.id, &v.changelog),
+                version: v,
+            })
+            .collect();
+
+        ModuleInfo {
+            config: module.to_owned(),
+            module: origin,
+            root,
+            versions,
+        }
+    }
+}
+
+pub struct InfoWrapper<'i> {
+    modules: &'i Vec<Arc<Module>>,
+    original: Arc<Info>,
+}
+
+impl<'i> InfoWrapper<'i> {
+    pub fn build(context: &'i Context) -> Self {
+        let info = Info::new(&context.modules_dir);
+
+        Self {
+            modules: &context.modules,
+            original: Arc::new(info),
+        }
+    }
+
+    pub fn list(&self) -> Vec<ModuleInfo> {
+        self.modules.iter().map(|m| self.info(m)).collect()
+    }
+
+    pub fn find(&self, id: &str) -> Option<ModuleInfo> {
+        self.modules
+            .iter()
+            .find(|m| m.id == id)
+            .map(|m| self.info(m))
+    }
+}
+
+impl Deref for InfoWrapper<'_> {
+    type Target = Info;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
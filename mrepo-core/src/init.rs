@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mrepo_model::config::{
+    Config, Log, Module, ModuleMetadata, ModuleSetting, ProviderKind, Repository,
+    RepositoryMetadata, RepositorySetting,
+};
+
+use crate::error::Error;
+use crate::format::Format;
+use crate::util::ConfigFormat;
+use crate::{constant, error};
+
+const GITIGNORE: &str = "\
+/json/.lock
+*.tmp
+*.bak
+/modules/*/tmp
+/modules/*/tmp.d
+/modules/*/stage.d
+";
+
+const EXAMPLE_HEADER: &str = "\
+# The initial config in TOML, `config.toml` and `config.yaml` are read as well
+# as `config.json`. Run `mrepo schema config` for every field.
+
+";
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>Modules</title>
+  <style>
+    body { font-family: sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }
+    li { margin-bottom: 1rem; }
+    small { color: #666; }
+  </style>
+</head>
+<body>
+  <h1 id="name">Modules</h1>
+  <ul id="modules"></ul>
+  <script>
+    fetch("json/modules.json")
+      .then((r) => r.json())
+      .then((index) => {
+        document.title = index.name;
+        document.getElementById("name").textContent = index.name;
+        const list = document.getElementById("modules");
+        for (const m of index.modules) {
+          const item = document.createElement("li");
+          const link = document.createElement("a");
+          link.href = m.versions[0].zip_url;
+          link.textContent = `${m.name} ${m.version}`;
+          const info = document.createElement("small");
+          info.textContent = ` by ${m.author} - ${m.description}`;
+          item.append(link, info);
+          list.append(item);
+        }
+      });
+  </script>
+</body>
+</html>
+"#;
+
+pub struct Init {
+    working_dir: PathBuf,
+}
+
+impl Init {
+    pub fn new<P: AsRef<Path>>(working_dir: P) -> Self {
+        Self {
+            working_dir: PathBuf::from(working_dir.as_ref()),
+        }
+    }
+
+    fn example_config(name: &str, base_url: &str) -> Config {
+        let repository = Repository {
+            name: name.to_owned(),
+            metadata: RepositoryMetadata::default(),
+            setting: RepositorySetting::new(base_url, 3),
+        };
+
+        let example = Module::new(
+            "example",
+            ProviderKind::UpdateJson,
+            "https://example.com/update.json",
+            "",
+            ModuleMetadata::default(),
+            ModuleSetting {
+                disabled: Some(true),
+                ..Default::default()
+            },
+        );
+
+        Config::new(Log::default(), repository, vec![example.into()])
+    }
+
+    fn example_toml(config: &Config) -> error::Result<String> {
+        let text = ConfigFormat::Toml.to_string(config)?;
+        Ok(format!("{EXAMPLE_HEADER}{text}"))
+    }
+
+    fn write_new<P: AsRef<Path>>(path: P, contents: &str) -> error::Result<()> {
+        let path = path.as_ref();
+        if path.exists() {
+            tracing::warn!(target: "Init::init", ?path, "Already exists, skipped");
+            return Ok(());
+        }
+
+        fs::write(path, contents).map_err(Error::io)
+    }
+
+    pub fn init(&self, name: &str, base_url: &str, gitignore: bool, landing_page: bool) -> bool {
+        let inner = || -> error::Result<()> {
+            let json_dir = self.working_dir.join(constant::JSON_DIR);
+            if let Some(path) = ConfigFormat::existing(&json_dir) {
+                return Err(Error::custom(format!(
+                    "Repository already initialized ({})",
+                    path.display()
+                )));
+            }
+
+            let config_path = json_dir.join(constant::CONFIG_JSON);
+            fs::create_dir_all(&json_dir).map_err(Error::io)?;
+            fs::create_dir_all(self.working_dir.join(constant::MODULES_DIR)).map_err(Error::io)?;
+            let config = Self::example_config(name, base_url);
+            Format::write_config(&config, &config_path)?;
+            Self::write_new(
+                json_dir.join(constant::CONFIG_EXAMPLE),
+                &Self::example_toml(&config)?,
+            )?;
+
+            if gitignore {
+                Self::write_new(self.working_dir.join(".gitignore"), GITIGNORE)?;
+            }
+
+            if landing_page {
+                Self::write_new(self.working_dir.join("index.html"), INDEX_HTML)?;
+            }
+
+            Ok(())
+        };
+
+        let path = &self.working_dir;
+        match inner() {
+            Ok(_) => {
+                tracing::info!(target: "Init::init", ?path, %name);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Init::init", ?path, ?error);
+                false
+            }
+        }
+    }
+}
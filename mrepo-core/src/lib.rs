@@ -4,18 +4,42 @@ use std::sync::Arc;
 
 use mrepo_model::config::Log;
 
+use crate::util::{ConfigFormat, Lock};
+
+pub use mrepo_model as model;
+
+pub use crate::backfill::{Backfill, BackfillWrapper, Source};
+pub use crate::check::{Check, CheckWrapper, Issue, IssueKind};
 pub use crate::context::Context;
+pub use crate::edit::{Edit, EditWrapper};
 pub use crate::format::{Format, FormatWrapper};
+pub use crate::import::{Import, ImportWrapper};
+pub use crate::info::{Info, InfoWrapper, ModuleInfo, VersionInfo};
+pub use crate::init::Init;
+pub use crate::rollback::{Rollback, RollbackWrapper};
+pub use crate::status::{Available, ModuleStatus, State, Status, StatusWrapper};
 pub use crate::update::{Update, UpdateWrapper};
 pub use crate::upgrade::{Upgrade, UpgradeWrapper};
+pub use crate::validate::{Diagnostic, Validate};
+pub use crate::yank::{Yank, YankWrapper};
 
+mod backfill;
+mod check;
 pub mod constant;
 mod context;
+mod edit;
 pub mod error;
 mod format;
+mod import;
+mod info;
+mod init;
+mod rollback;
+mod status;
 mod update;
 mod upgrade;
 pub mod util;
+mod validate;
+mod yank;
 
 pub struct ContextWrapper {
     original: Arc<Context>,
@@ -38,7 +62,7 @@ impl ContextWrapper {
         let working_dir = working_dir.as_ref().to_owned();
         let json_dir = working_dir.join(constant::JSON_DIR);
         let modules_dir = working_dir.join(constant::MODULES_DIR);
-        let config_path = json_dir.join(constant::CONFIG_JSON);
+        let config_path = ConfigFormat::find(&json_dir);
 
         let context = Context::new(config_path, json_dir, modules_dir)?;
         Ok(Self {
@@ -50,17 +74,53 @@ impl ContextWrapper {
         init(&self.log)
     }
 
+    pub fn info(&self) -> InfoWrapper<'_> {
+        InfoWrapper::build(self)
+    }
+
+    pub fn lock(&self, wait: bool) -> error::Result<Lock> {
+        Lock::acquire(Lock::path(&self.modules_dir), wait)
+    }
+
+    pub fn backfill(&self) -> BackfillWrapper<'_> {
+        BackfillWrapper::build(self)
+    }
+
+    pub fn check(&self) -> CheckWrapper<'_> {
+        CheckWrapper::build(self)
+    }
+
+    pub fn edit(&self) -> EditWrapper {
+        EditWrapper::build(self)
+    }
+
     pub fn format(&self) -> FormatWrapper {
         FormatWrapper::build(self)
     }
 
-    pub fn update(&self) -> UpdateWrapper {
+    pub fn import(&self) -> ImportWrapper {
+        ImportWrapper::build(self)
+    }
+
+    pub fn rollback(&self) -> RollbackWrapper {
+        RollbackWrapper::build(self)
+    }
+
+    pub fn status(&self) -> StatusWrapper<'_> {
+        StatusWrapper::build(self)
+    }
+
+    pub fn update(&self) -> UpdateWrapper<'_> {
         UpdateWrapper::build(self)
     }
 
-    pub fn upgrade(&self) -> UpgradeWrapper {
+    pub fn upgrade(&self) -> UpgradeWrapper<'_> {
         UpgradeWrapper::build(self)
     }
+
+    pub fn yank(&self) -> YankWrapper {
+        YankWrapper::build(self)
+    }
 }
 
 impl Deref for ContextWrapper {
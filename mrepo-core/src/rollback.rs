@@ -0,0 +1,115 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mrepo_model::track::Track;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::util::{Json, LocalModule, Lock};
+use crate::{constant, error};
+
+pub struct Rollback {
+    modules_dir: PathBuf,
+}
+
+impl Rollback {
+    pub fn new<P: AsRef<Path>>(modules_dir: P) -> Self {
+        Self {
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn rollback_track(&self, id: &str, track: &mut Track, to: Option<i64>) -> error::Result<i64> {
+        let module_dir = self.modules_dir.join(id);
+        let current = match track.versions.iter().position(|v| !v.withdrawn) {
+            Some(i) => i,
+            None => return Err(Error::custom("No published version")),
+        };
+
+        let target = match to {
+            Some(version_code) => track
+                .versions
+                .iter()
+                .position(|v| v.version_code == version_code)
+                .ok_or_else(|| Error::custom(format!("Version `{version_code}` not found")))?,
+            None => track
+                .versions
+                .iter()
+                .enumerate()
+                .skip(current + 1)
+                .find(|(_, v)| !v.withdrawn && module_dir.join(&v.zip_file).is_file())
+                .map(|(i, _)| i)
+                .ok_or_else(|| Error::custom("No previous version retained"))?,
+        };
+
+        let version = &track.versions[target];
+        if !module_dir.join(&version.zip_file).is_file() {
+            return Err(Error::custom(format!(
+                "Version `{}` is no longer retained",
+                version.version_code
+            )));
+        }
+
+        for version in track.versions.iter_mut().take(target) {
+            version.withdrawn = true;
+        }
+
+        let version = &mut track.versions[target];
+        version.withdrawn = false;
+
+        match LocalModule::read_zip(module_dir.join(&version.zip_file)) {
+            Some(prop) => track.module = prop,
+            None => {
+                track.module.version = version.version.to_owned();
+                track.module.version_code = version.version_code;
+            }
+        }
+
+        Ok(version.version_code)
+    }
+
+    pub fn rollback(&self, id: &str, to: Option<i64>) -> bool {
+        let track_json = self.modules_dir.join(id).join(constant::TRACK_JSON);
+        let inner = || -> error::Result<i64> {
+            let _lock = Lock::acquire(Lock::path(&self.modules_dir), false)?;
+            let mut track = Track::from_file(&track_json)?;
+            let version_code = self.rollback_track(id, &mut track, to)?;
+            track.to_file(&track_json, true)?;
+            Ok(version_code)
+        };
+
+        match inner() {
+            Ok(version_code) => {
+                tracing::info!(target: "Rollback::rollback", %id, %version_code);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Rollback::rollback", %id, ?error);
+                false
+            }
+        }
+    }
+}
+
+pub struct RollbackWrapper {
+    original: Arc<Rollback>,
+}
+
+impl RollbackWrapper {
+    pub fn build(context: &Context) -> Self {
+        let rollback = Rollback::new(&context.modules_dir);
+
+        Self {
+            original: Arc::new(rollback),
+        }
+    }
+}
+
+impl Deref for RollbackWrapper {
+    type Target = Rollback;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
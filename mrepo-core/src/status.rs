@@ -0,0 +1,283 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::DateTime;
+use tokio::task::JoinHandle;
+
+use mrepo_model::config::{Module, ProviderKind};
+use mrepo_model::origin::{GithubRelease, UpdateJson};
+use mrepo_model::track::{Track, Version};
+
+use crate::backfill::Source;
+use crate::util::{Json, Mirror, Request, StrUtil};
+use crate::{constant, Context};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Available {
+    Version(String, i64),
+    Release(String, i64),
+    Modified(i64),
+    Unknown,
+    Failed,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum State {
+    Outdated,
+    Latest,
+    Held,
+    Disabled,
+    Unknown,
+}
+
+impl State {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            State::Outdated => "outdated",
+            State::Latest => "latest",
+            State::Held => "held",
+            State::Disabled => "disabled",
+            State::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModuleStatus {
+    pub id: String,
+    pub kind: ProviderKind,
+    pub current: Option<Version>,
+    pub available: Available,
+    pub state: State,
+}
+
+pub struct Status {
+    modules_dir: PathBuf,
+}
+
+impl Status {
+    pub fn new<P: AsRef<Path>>(modules_dir: P) -> Self {
+        Self {
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn read_track(&self, module: &Module) -> Option<Track> {
+        let track_json = self.modules_dir.join(&module.id).join(constant::TRACK_JSON);
+        if !track_json.exists() {
+            return None;
+        }
+
+        Track::from_file(track_json).ok()
+    }
+
+    fn github(url: &str) -> Option<(String, String)> {
+        match Source::github(url)? {
+            Source::Github(owner, repo) => Some((owner, repo)),
+            _ => None,
+        }
+    }
+
+    fn parse_time(time: &str) -> Option<i64> {
+        DateTime::parse_from_rfc3339(time)
+            .ok()
+            .map(|t| t.timestamp_millis())
+    }
+
+    async fn query_release(owner: &str, repo: &str) -> Available {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/latest");
+        let release = match Request::github(&url).await {
+            Some(request) => request.json::<GithubRelease>().await,
+            None => None,
+        };
+
+        match release {
+            Some(r) => match Self::parse_time(&r.published_at) {
+                Some(t) => Available::Release(r.tag_name, t),
+                None => Available::Unknown,
+            },
+            None => Available::Failed,
+        }
+    }
+
+    #[cfg(feature = "git")]
+    async fn query_git(module: &Module) -> Available {
+        use crate::util::Git;
+        use mrepo_model::origin::GithubCommit;
+
+        if let Some((owner, repo)) = Self::github(&module.provider) {
+            let url = format!("https://api.github.com/repos/{owner}/{repo}/commits/HEAD");
+            let commit = match Request::github(&url).await {
+                Some(request) => request.json::<GithubCommit>().await,
+                None => None,
+            };
+
+            return match commit {
+                Some(c) => match Self::parse_time(&c.commit.committer.date) {
+                    Some(t) => Available::Modified(t),
+                    None => Available::Unknown,
+                },
+                None => Available::Failed,
+            };
+        }
+
+        // Only the HEAD commit is known without fetching, which can't be
+        // compared against track.json.
+        match Git::ls_remote(&module.provider).await {
+            Some(_) => Available::Unknown,
+            None => Available::Failed,
+        }
+    }
+
+    async fn query(&self, module: &Module) -> Available {
+        match module.kind {
+            ProviderKind::UpdateJson => {
+                match Request::load_json::<UpdateJson>(&module.provider).await {
+                    Some(u) => Available::Version(
+                        StrUtil::get_version_display(&u.version, u.version_code),
+                        u.version_code,
+                    ),
+                    None => Available::Failed,
+                }
+            }
+            ProviderKind::ZipUrl => match Self::github(&module.provider)
+                .filter(|_| module.provider.contains("/releases/latest/download/"))
+            {
+                Some((owner, repo)) => Self::query_release(&owner, &repo).await,
+                None => match Request::head(&module.provider).await {
+                    Some(request) => match request.last_modified() {
+                        Some(t) => Available::Modified(t.timestamp_millis()),
+                        None => Available::Unknown,
+                    },
+                    None => Available::Failed,
+                },
+            },
+            ProviderKind::Mirror => match Mirror::load(&module.provider, &module.id).await {
+                Some(upstream) => match Mirror::latest(&upstream) {
+                    Some(v) => Available::Version(v.version.to_owned(), v.version_code),
+                    None => Available::Unknown,
+                },
+                None => Available::Failed,
+            },
+            #[cfg(feature = "git")]
+            ProviderKind::Git => Self::query_git(module).await,
+        }
+    }
+
+    fn get_state(module: &Module, track: Option<&Track>, available: &Available) -> State {
+        if module.setting.is_disabled() {
+            return State::Disabled;
+        }
+
+        let track = match track {
+            Some(t) => t,
+            None => {
+                return match available {
+                    Available::Failed => State::Unknown,
+                    _ => State::Outdated,
+                }
+            }
+        };
+
+        let pin = module.setting.pin_version_code;
+        match available {
+            Available::Version(_, version_code) if pin.is_some_and(|pin| *version_code > pin) => {
+                State::Held
+            }
+            Available::Version(_, version_code) => {
+                let known = track
+                    .versions
+                    .iter()
+                    .any(|v| v.version_code == *version_code);
+                if *version_code <= track.module.version_code || known {
+                    State::Latest
+                } else {
+                    State::Outdated
+                }
+            }
+            Available::Modified(timestamp) | Available::Release(_, timestamp) => {
+                let latest = track.versions.iter().map(|v| v.timestamp).max();
+                if latest.is_some_and(|t| t >= *timestamp) {
+                    State::Latest
+                } else if pin.is_some() {
+                    State::Held
+                } else {
+                    State::Outdated
+                }
+            }
+            Available::Unknown | Available::Failed => State::Unknown,
+        }
+    }
+
+    pub async fn status(&self, module: &Module) -> ModuleStatus {
+        let track = self.read_track(module);
+        let available = if module.setting.is_disabled() {
+            Available::Unknown
+        } else {
+            self.query(module).await
+        };
+
+        let state = Self::get_state(module, track.as_ref(), &available);
+        let pin = module.setting.pin_version_code.unwrap_or(i64::MAX);
+        let current = track.and_then(|t| {
+            t.versions
+                .into_iter()
+                .find(|v| !v.withdrawn && v.version_code <= pin)
+        });
+
+        ModuleStatus {
+            id: module.id.to_owned(),
+            kind: module.kind,
+            current,
+            available,
+            state,
+        }
+    }
+}
+
+pub struct StatusWrapper<'s> {
+    modules: &'s Vec<Arc<Module>>,
+    original: Arc<Status>,
+}
+
+impl<'s> StatusWrapper<'s> {
+    pub fn build(context: &'s Context) -> Self {
+        let status = Status::new(&context.modules_dir);
+
+        Self {
+            modules: &context.modules,
+            original: Arc::new(status),
+        }
+    }
+
+    pub async fn status_all(&self, module_ids: &[String]) -> Vec<ModuleStatus> {
+        let tasks: Vec<JoinHandle<ModuleStatus>> = self
+            .modules
+            .iter()
+            .filter(|m| module_ids.is_empty() || module_ids.contains(&m.id))
+            .map(|m| {
+                let module = m.to_owned();
+                let original = self.original.to_owned();
+                tokio::spawn(async move { original.status(&module).await })
+            })
+            .collect();
+
+        let mut statuses = Vec::new();
+        for task in tasks {
+            if let Ok(status) = task.await {
+                statuses.push(status);
+            }
+        }
+
+        statuses
+    }
+}
+
+impl Deref for StatusWrapper<'_> {
+    type Target = Status;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(unused_assignments)]
 
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -7,14 +8,15 @@ use std::sync::Arc;
 use chrono::Utc;
 use tokio::task::JoinHandle;
 
-use mrepo_model::config::{Module, ProviderKind, RepositorySetting};
+use mrepo_model::config::{Module, ProviderKind, RepositorySetting, Retention, VersionPolicy};
+use mrepo_model::modules;
 use mrepo_model::origin;
 use mrepo_model::origin::UpdateJson;
 use mrepo_model::track::{Track, Version};
 
 use crate::constant;
-use crate::util::{FileUtil, Json, LocalModule, Request, StrUtil};
-use crate::Context;
+use crate::util::{FileUtil, Json, LocalModule, Lock, Mirror, Request, StrUtil};
+use crate::{Context, Validate};
 
 pub struct Update {
     setting: RepositorySetting,
@@ -35,8 +37,8 @@ impl Update {
         version: &str,
         version_code: i64,
     ) -> Option<Vec<Version>> {
+        let version_display = StrUtil::get_version_display(version, version_code);
         let inner = || {
-            let version_display = StrUtil::get_version_display(version, version_code);
             tracing::info!(
                 target: "Update::check_versions",
                 id = %module.id,
@@ -47,7 +49,7 @@ impl Update {
         let module_dir = self.modules_dir.join(&module.id);
         let track_json = module_dir.join(constant::TRACK_JSON);
 
-        let mut track = match Track::from_file(track_json) {
+        let track = match Track::from_file(track_json) {
             Ok(t) => t,
             Err(_) => {
                 inner();
@@ -55,38 +57,292 @@ impl Update {
             }
         };
 
-        if track.module.version_code >= version_code {
+        let withdrawn = track
+            .versions
+            .iter()
+            .any(|v| v.version_code == version_code && v.withdrawn);
+        if withdrawn {
             tracing::info!(
                 target: "Update::check_versions",
                 id = %module.id,
-                "Already latest version"
+                version = %version_display,
+                "Version withdrawn, ignored"
             );
 
-            None
-        } else {
-            track
-                .versions
-                .sort_by(|a, b| b.version_code.cmp(&a.version_code));
+            return None;
+        }
 
+        // Versions are kept in publish order rather than sorted by version_code,
+        // `versions[0]` is the latest even after an accepted downgrade.
+        let latest = track.module.version_code;
+        if version_code > latest {
             inner();
-            Some(track.versions)
+            return Some(track.versions);
+        }
+
+        if version_code == latest {
+            if module.setting.reupload_policy() == VersionPolicy::Ignore {
+                tracing::info!(
+                    target: "Update::check_versions",
+                    id = %module.id,
+                    "Already latest version"
+                );
+
+                return None;
+            }
+
+            return Some(track.versions);
+        }
+
+        match module.setting.downgrade_policy() {
+            VersionPolicy::Ignore => {
+                tracing::info!(
+                    target: "Update::check_versions",
+                    id = %module.id,
+                    version = %version_display,
+                    %latest,
+                    "Older version found, ignored"
+                );
+
+                None
+            }
+            VersionPolicy::Warn => {
+                tracing::warn!(
+                    target: "Update::check_versions",
+                    id = %module.id,
+                    version = %version_display,
+                    %latest,
+                    "Older version found, downgrade not accepted"
+                );
+
+                None
+            }
+            VersionPolicy::Accept => {
+                tracing::warn!(
+                    target: "Update::check_versions",
+                    id = %module.id,
+                    version = %version_display,
+                    %latest,
+                    "Older version found, downgrade accepted"
+                );
+
+                Some(track.versions)
+            }
         }
     }
 
-    fn keep_size(&self, module: &Module) -> usize {
+    fn check_reupload(
+        &self,
+        module: &Module,
+        version_code: i64,
+        checksum: &str,
+        old_checksum: &str,
+    ) -> bool {
+        match module.setting.reupload_policy() {
+            VersionPolicy::Accept => {
+                tracing::warn!(
+                    target: "Update::check_checksum",
+                    id = %module.id,
+                    %version_code,
+                    %checksum,
+                    old = %old_checksum,
+                    "Checksum changed, replacing version"
+                );
+
+                true
+            }
+            VersionPolicy::Ignore => {
+                tracing::warn!(
+                    target: "Update::check_checksum",
+                    id = %module.id,
+                    %version_code,
+                    %checksum,
+                    old = %old_checksum,
+                    "Checksum changed, reupload ignored"
+                );
+
+                false
+            }
+            VersionPolicy::Warn => {
+                tracing::warn!(
+                    target: "Update::check_checksum",
+                    id = %module.id,
+                    %version_code,
+                    %checksum,
+                    old = %old_checksum,
+                    "Checksum changed, reupload not accepted"
+                );
+
+                false
+            }
+        }
+    }
+
+    fn old_checksum(&self, module: &Module, old: &Version) -> String {
+        if old.checksum.is_empty() {
+            let module_dir = self.modules_dir.join(&module.id);
+            FileUtil::sha256(module_dir.join(&old.zip_file)).unwrap_or_default()
+        } else {
+            old.checksum.to_owned()
+        }
+    }
+
+    fn check_checksum(
+        &self,
+        module: &Module,
+        versions: &[Version],
+        version_code: i64,
+        checksum: &str,
+    ) -> bool {
+        let index = match versions.iter().position(|v| v.version_code == version_code) {
+            Some(i) => i,
+            None => return true,
+        };
+
+        let old_checksum = self.old_checksum(module, &versions[index]);
+        if old_checksum == checksum {
+            if index == 0 {
+                tracing::info!(
+                    target: "Update::check_checksum",
+                    id = %module.id,
+                    "Already latest version"
+                );
+
+                return false;
+            }
+        } else {
+            return self.check_reupload(module, version_code, checksum, &old_checksum);
+        }
+
+        true
+    }
+
+    pub(crate) fn keep_size(&self, module: &Module) -> usize {
         let size = module.setting.keep_size.unwrap_or(self.setting.keep_size);
 
         tracing::debug!(target: "keep_size", id = %module.id, %size);
         size
     }
 
-    fn remove_old(&self, module: &Module, old: &[Version]) {
+    fn retention<'a>(&'a self, module: &'a Module) -> &'a Retention {
+        module
+            .setting
+            .retention
+            .as_ref()
+            .unwrap_or(&self.setting.retention)
+    }
+
+    fn split_old(&self, module: &Module, versions: Vec<Version>) -> (Vec<Version>, Vec<Version>) {
+        let module_dir = self.modules_dir.join(&module.id);
+        let keep_size = self.keep_size(module);
+        let retention = self.retention(module);
+        let min_keep = retention.min_keep.unwrap_or(1).max(1);
+
+        let newer_than = match retention.keep_newer_than.as_str() {
+            "" => None,
+            value => match humantime::parse_duration(value) {
+                Ok(d) => Some(Utc::now().timestamp_millis() - d.as_millis() as i64),
+                Err(error) => {
+                    tracing::error!(target: "Update::split_old", id = %module.id, %value, ?error);
+                    None
+                }
+            },
+        };
+
+        let mut major_firsts: HashMap<u64, i64> = HashMap::new();
+        if retention.keep_major_first {
+            for v in &versions {
+                if let Some(major) = StrUtil::get_major_version(&v.version) {
+                    let first = major_firsts.entry(major).or_insert(v.version_code);
+                    *first = (*first).min(v.version_code);
+                }
+            }
+        }
+
+        let pinned = module.setting.pin_version_code.and_then(|pin| {
+            versions
+                .iter()
+                .find(|v| !v.withdrawn && v.version_code <= pin)
+                .map(|v| v.version_code)
+        });
+
+        let mut size = 0;
+        let mut kept = Vec::new();
+        let mut old = Vec::new();
+        for v in versions {
+            if !v.yanked && !v.withdrawn {
+                size += 1;
+            }
+
+            let protected = size <= min_keep
+                || pinned == Some(v.version_code)
+                || newer_than.is_some_and(|t| v.timestamp >= t)
+                || StrUtil::get_major_version(&v.version)
+                    .is_some_and(|m| major_firsts.get(&m) == Some(&v.version_code));
+
+            if size <= keep_size || protected {
+                kept.push((protected, v));
+            } else {
+                old.push(v);
+            }
+        }
+
+        if let Some(max_bytes) = retention.max_bytes {
+            let stage_dir = module_dir.join(constant::STAGE_DIR);
+            let file_size = |f: &String| {
+                let staged = FileUtil::size(stage_dir.join(f));
+                if staged > 0 {
+                    staged
+                } else {
+                    FileUtil::size(module_dir.join(f))
+                }
+            };
+            let bytes = |v: &Version| file_size(&v.zip_file) + file_size(&v.changelog);
+
+            let mut total: u64 = kept.iter().map(|(_, v)| bytes(v)).sum();
+            while total > max_bytes {
+                let index = match kept.iter().rposition(|(protected, _)| !protected) {
+                    Some(i) => i,
+                    None => break,
+                };
+
+                let (_, v) = kept.remove(index);
+                total -= bytes(&v);
+                old.push(v);
+            }
+
+            if total > max_bytes {
+                tracing::warn!(
+                    target: "Update::split_old",
+                    id = %module.id,
+                    %total,
+                    %max_bytes,
+                    %min_keep,
+                    "Size cap exceeded by protected versions"
+                );
+            }
+        }
+
+        for v in &old {
+            tracing::debug!(target: "Update::split_old", id = %module.id, version_code = %v.version_code);
+        }
+
+        (kept.into_iter().map(|(_, v)| v).collect(), old)
+    }
+
+    fn remove_old(&self, module: &Module, kept: &[Version], old: &[Version]) {
         let module_dir = self.modules_dir.join(&module.id);
+        let is_kept = |file: &String| {
+            kept.iter()
+                .any(|v| &v.zip_file == file || &v.changelog == file)
+        };
 
-        old.iter().for_each(|v| {
-            FileUtil::remove(module_dir.join(&v.zip_file));
-            FileUtil::remove(module_dir.join(&v.changelog));
-        })
+        old.iter()
+            .flat_map(|v| [&v.zip_file, &v.changelog])
+            .filter(|f| !f.is_empty() && !is_kept(f))
+            .for_each(|f| {
+                FileUtil::remove(module_dir.join(f));
+            })
     }
 
     fn write_track(&self, module: &Module, track: &Track) -> bool {
@@ -97,53 +353,135 @@ impl Update {
             Ok(_) => true,
             Err(error) => {
                 tracing::error!(target: "Update::write_track", id = %module.id, ?error);
+                false
+            }
+        }
+    }
 
-                let version = &track.versions[0];
-                FileUtil::remove(module_dir.join(&version.zip_file));
-                FileUtil::remove(module_dir.join(&version.changelog));
+    fn commit(&self, module: &Module, track: &Track) -> bool {
+        let module_dir = self.modules_dir.join(&module.id);
+        let stage_dir = module_dir.join(constant::STAGE_DIR);
+        let files = track
+            .versions
+            .iter()
+            .flat_map(|v| [&v.zip_file, &v.changelog])
+            .filter(|f| !f.is_empty() && stage_dir.join(f).exists());
+
+        let mut created = Vec::new();
+        let mut is_ok = true;
+        for file in files {
+            let path = module_dir.join(file);
+            let exists = path.exists();
+            if !FileUtil::rename(stage_dir.join(file), path.to_owned()) {
+                is_ok = false;
+                break;
+            }
 
-                false
+            if !exists {
+                created.push(path);
             }
         }
+
+        is_ok = is_ok && self.write_track(module, track);
+        if !is_ok {
+            created.iter().for_each(|p| {
+                FileUtil::remove(p);
+            });
+        }
+
+        FileUtil::remove(&stage_dir);
+        is_ok
+    }
+
+    fn stage_zip(
+        &self,
+        module: &Module,
+        zip_tmp: &Path,
+        version: &mut Version,
+        checksum: String,
+        replaced: &[Version],
+    ) -> bool {
+        let stage_dir = self.modules_dir.join(&module.id).join(constant::STAGE_DIR);
+        let conflict = replaced
+            .iter()
+            .any(|v| v.zip_file == version.zip_file && v.checksum != checksum);
+        if conflict {
+            let suffix = &checksum[..8];
+            version.zip_file = format!("{}.{suffix}.zip", version.version_code);
+            version.changelog = format!("{}.{suffix}.md", version.version_code);
+        }
+        version.checksum = checksum;
+
+        if !FileUtil::rename(zip_tmp, &stage_dir.join(&version.zip_file)) {
+            FileUtil::remove(zip_tmp);
+            return false;
+        }
+
+        true
+    }
+
+    async fn stage_changelog(&self, module: &Module, version: &mut Version, url: &str) {
+        let stage_dir = self.modules_dir.join(&module.id).join(constant::STAGE_DIR);
+        let changelog = stage_dir.join(&version.changelog);
+        let is_ok = Request::write_file(url, &changelog).await;
+        if !is_ok || FileUtil::is_html(&changelog) {
+            version.changelog = String::new();
+            FileUtil::remove(&changelog);
+        }
     }
 
     async fn update_common(
         &self,
         module: &Module,
         module_new: origin::Module,
-        mut versions: Vec<Version>,
+        versions: Vec<Version>,
         timestamp: i64,
         changelog_url: &str,
     ) -> bool {
         let module_dir = self.modules_dir.join(&module.id);
         let zip_tmp = module_dir.join(constant::TMP_FILE);
+        let stage_dir = module_dir.join(constant::STAGE_DIR);
+
+        let checksum = match FileUtil::sha256(&zip_tmp) {
+            Some(c) => c,
+            None => {
+                FileUtil::remove(&zip_tmp);
+                return false;
+            }
+        };
+
+        if !self.check_checksum(module, &versions, module_new.version_code, &checksum) {
+            FileUtil::remove(&zip_tmp);
+            return false;
+        }
 
         let version = StrUtil::get_version_display(&module_new.version, module_new.version_code);
         let mut version = Version::new(timestamp, version, module_new.version_code);
-        FileUtil::rename(&zip_tmp, &module_dir.join(&version.zip_file));
+        let (replaced, mut versions): (Vec<Version>, Vec<Version>) = versions
+            .into_iter()
+            .partition(|v| v.version_code == version.version_code);
 
-        let changelog = module_dir.join(&version.changelog);
-        let is_ok = Request::write_file(changelog_url, &changelog).await;
-        if !is_ok || FileUtil::is_html(&changelog) {
-            version.changelog = String::new();
-            FileUtil::remove(&changelog);
+        FileUtil::remove(&stage_dir);
+        if !self.stage_zip(module, &zip_tmp, &mut version, checksum, &replaced) {
+            return false;
         }
+        self.stage_changelog(module, &mut version, changelog_url)
+            .await;
 
         versions.insert(0, version);
-        let keep_size = self.keep_size(module);
-        if versions.len() > keep_size {
-            let old = versions.split_off(keep_size);
-            self.remove_old(module, &old);
+        let (versions, mut old) = self.split_old(module, versions);
+        old.extend(replaced);
+
+        let track = Track::new(module_new, versions);
+        if !self.commit(module, &track) {
+            return false;
         }
 
-        let track = Track {
-            module: module_new,
-            versions,
-        };
-        self.write_track(module, &track)
+        self.remove_old(module, &track.versions, &old);
+        true
     }
 
-    pub async fn update_by_json(&self, module: &Module) -> bool {
+    async fn update_by_json(&self, module: &Module) -> bool {
         let module_dir = self.modules_dir.join(&module.id);
         let mut timestamp = Utc::now().timestamp_millis();
 
@@ -191,7 +529,7 @@ impl Update {
         ).await
     }
 
-    pub async fn update_by_url(&self, module: &Module) -> bool {
+    async fn update_by_url(&self, module: &Module) -> bool {
         let module_dir = self.modules_dir.join(&module.id);
         let mut timestamp = Utc::now().timestamp_millis();
 
@@ -238,7 +576,7 @@ impl Update {
     }
 
     #[cfg(feature = "git")]
-    pub async fn update_by_git(&self, module: &Module) -> bool {
+    async fn update_by_git(&self, module: &Module) -> bool {
         use crate::util::Git;
 
         let module_dir = self.modules_dir.join(&module.id);
@@ -287,16 +625,268 @@ impl Update {
         ).await
     }
 
+    pub(crate) async fn stage_mirror(
+        &self,
+        module: &Module,
+        upstream: &modules::Version,
+        local: Option<&Version>,
+    ) -> Option<Version> {
+        let zip_tmp = self.modules_dir.join(&module.id).join(constant::TMP_FILE);
+        let mut version = Version::new(
+            upstream.timestamp,
+            upstream.version.to_owned(),
+            upstream.version_code,
+        );
+
+        if !Request::write_file(&upstream.zip_url, &zip_tmp).await {
+            FileUtil::remove(&zip_tmp);
+            return None;
+        }
+
+        let checksum = match FileUtil::sha256(&zip_tmp) {
+            Some(c) => c,
+            None => {
+                FileUtil::remove(&zip_tmp);
+                return None;
+            }
+        };
+
+        let replaced: Vec<Version> = local.into_iter().cloned().collect();
+        if let Some(local) = local {
+            let old_checksum = self.old_checksum(module, local);
+            if old_checksum == checksum
+                || !self.check_reupload(module, version.version_code, &checksum, &old_checksum)
+            {
+                FileUtil::remove(&zip_tmp);
+                return None;
+            }
+        }
+
+        if !self.stage_zip(module, &zip_tmp, &mut version, checksum, &replaced) {
+            return None;
+        }
+        self.stage_changelog(module, &mut version, &upstream.changelog)
+            .await;
+
+        version.yanked = upstream.yanked;
+        version.yanked_reason = upstream.yanked_reason.to_owned();
+        Some(version)
+    }
+
+    pub(crate) fn merge_staged(
+        &self,
+        module: &Module,
+        track_module: Option<origin::Module>,
+        versions: Vec<Version>,
+        mut staged: Vec<Version>,
+    ) -> bool {
+        let module_dir = self.modules_dir.join(&module.id);
+        let stage_dir = module_dir.join(constant::STAGE_DIR);
+        let codes: Vec<i64> = staged.iter().map(|v| v.version_code).collect();
+
+        // Staged versions take the place of the ones they replace, the rest
+        // go before the first known version with a lower code so the known
+        // order is never re-sorted.
+        let mut replaced = Vec::new();
+        let mut merged = Vec::new();
+        for v in versions {
+            match staged.iter().position(|s| s.version_code == v.version_code) {
+                Some(i) => {
+                    merged.push(staged.remove(i));
+                    replaced.push(v);
+                }
+                None => merged.push(v),
+            }
+        }
+        for v in staged {
+            let index = merged
+                .iter()
+                .position(|m| m.version_code < v.version_code)
+                .unwrap_or(merged.len());
+            merged.insert(index, v);
+        }
+
+        let (versions, mut old) = self.split_old(module, merged);
+        old.extend(replaced);
+
+        let latest = match versions.first() {
+            Some(v) => v,
+            None => {
+                FileUtil::remove(&stage_dir);
+                return false;
+            }
+        };
+
+        let staged = if codes.contains(&latest.version_code) {
+            LocalModule::read_zip(stage_dir.join(&latest.zip_file))
+        } else {
+            None
+        };
+        let module_new = match staged.or(track_module) {
+            Some(m) => m,
+            None => {
+                tracing::error!(target: "Update::merge_staged", id = %module.id, "No module.prop found");
+                FileUtil::remove(&stage_dir);
+                return false;
+            }
+        };
+
+        let track = Track::new(module_new, versions);
+        if !self.commit(module, &track) {
+            return false;
+        }
+
+        self.remove_old(module, &track.versions, &old);
+        true
+    }
+
+    async fn update_by_mirror(&self, module: &Module) -> bool {
+        match Mirror::load(&module.provider, &module.id).await {
+            Some(upstream) => self.update_by_upstream(module, &upstream).await,
+            None => false,
+        }
+    }
+
+    pub(crate) async fn update_by_upstream(
+        &self,
+        module: &Module,
+        upstream: &modules::Module,
+    ) -> bool {
+        let module_dir = self.modules_dir.join(&module.id);
+        let (track_module, mut local) =
+            match Track::from_file(module_dir.join(constant::TRACK_JSON)) {
+                Ok(t) => (Some(t.module), t.versions),
+                Err(_) => (None, Vec::new()),
+            };
+
+        // The upstream keeps publish order as well, its first version goes
+        // through the same downgrade checks as any other provider.
+        let accepted = match Mirror::latest(upstream) {
+            Some(v) => self
+                .check_versions(module, &v.version, v.version_code)
+                .is_some(),
+            None => false,
+        };
+
+        let mut changed = false;
+        let mut added = Vec::new();
+        let mut reuploaded = Vec::new();
+        for (i, v) in upstream.versions.iter().enumerate() {
+            match local.iter_mut().find(|l| l.version_code == v.version_code) {
+                Some(l) if !l.withdrawn => {
+                    if l.yanked != v.yanked || l.yanked_reason != v.yanked_reason {
+                        l.yanked = v.yanked;
+                        l.yanked_reason = v.yanked_reason.to_owned();
+                        changed = true;
+                    }
+                    if l.timestamp != v.timestamp {
+                        reuploaded.push(v);
+                    }
+                }
+                Some(_) => {}
+                None if i == 0 && !accepted => {}
+                None => added.push(v),
+            }
+        }
+
+        let placeholder =
+            |v: &modules::Version| Version::new(v.timestamp, v.version.to_owned(), v.version_code);
+        let mut versions: Vec<Version> = if accepted {
+            let mut ordered: Vec<Version> = upstream
+                .versions
+                .iter()
+                .filter_map(
+                    |v| match local.iter().find(|l| l.version_code == v.version_code) {
+                        Some(l) => Some(l.to_owned()),
+                        None if added.contains(&v) => Some(placeholder(v)),
+                        None => None,
+                    },
+                )
+                .collect();
+            for l in &local {
+                if !ordered.iter().any(|v| v.version_code == l.version_code) {
+                    ordered.push(l.to_owned());
+                }
+            }
+            ordered
+        } else {
+            let mut ordered = local.to_owned();
+            ordered.extend(added.iter().map(|v| placeholder(v)));
+            ordered
+        };
+
+        let known: Vec<i64> = local.iter().map(|l| l.version_code).collect();
+        let reordered = versions
+            .iter()
+            .map(|v| v.version_code)
+            .filter(|c| known.contains(c))
+            .ne(known.iter().copied());
+
+        let (kept, _) = self.split_old(module, versions.to_owned());
+        let is_kept = |v: &&modules::Version| kept.iter().any(|k| k.version_code == v.version_code);
+        let added: Vec<&modules::Version> = added.into_iter().filter(is_kept).collect();
+        let reuploaded: Vec<&modules::Version> = reuploaded.into_iter().filter(is_kept).collect();
+
+        if added.is_empty() && reuploaded.is_empty() && !changed && !reordered {
+            tracing::info!(target: "Update::update_by_mirror", id = %module.id, "Already latest version");
+            return false;
+        }
+
+        let mut staged = Vec::new();
+        for upstream in added {
+            match self.stage_mirror(module, upstream, None).await {
+                Some(v) => {
+                    tracing::info!(target: "Update::update_by_mirror", id = %module.id, version = %v.version, "New version found");
+                    staged.push(v);
+                }
+                None => {
+                    tracing::warn!(target: "Update::update_by_mirror", id = %module.id, version_code = %upstream.version_code, "Failed to download version");
+                    versions.retain(|v| v.version_code != upstream.version_code);
+                }
+            }
+        }
+
+        for upstream in reuploaded {
+            let local = local
+                .iter()
+                .find(|l| l.version_code == upstream.version_code);
+            if let Some(v) = self.stage_mirror(module, upstream, local).await {
+                staged.push(v);
+            }
+        }
+
+        if staged.is_empty() && !changed && !reordered {
+            tracing::info!(target: "Update::update_by_mirror", id = %module.id, "Already latest version");
+            FileUtil::remove(module_dir.join(constant::STAGE_DIR));
+            return false;
+        }
+
+        self.merge_staged(module, track_module, versions, staged)
+    }
+
     pub async fn update(&self, module: &Module) -> bool {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        self.update_locked(module).await
+    }
+
+    async fn update_locked(&self, module: &Module) -> bool {
         tracing::debug!(target: "Update::update", ?module);
-        if module.setting.disabled {
+        if module.setting.is_disabled() {
             return false;
         }
 
         tracing::info!(target: "Update::update", id = %module.id, kind = ?module.kind);
+        let module_dir = self.modules_dir.join(&module.id);
+        FileUtil::remove(module_dir.join(constant::STAGE_DIR));
+
         match module.kind {
             ProviderKind::UpdateJson => self.update_by_json(module).await,
             ProviderKind::ZipUrl => self.update_by_url(module).await,
+            ProviderKind::Mirror => self.update_by_mirror(module).await,
             #[cfg(feature = "git")]
             ProviderKind::Git => self.update_by_git(module).await,
         }
@@ -304,6 +894,7 @@ impl Update {
 }
 
 pub struct UpdateWrapper<'u> {
+    config_path: &'u Path,
     modules: &'u Vec<Arc<Module>>,
     original: Arc<Update>,
 }
@@ -313,12 +904,22 @@ impl<'u> UpdateWrapper<'u> {
         let update = Update::new(&context.repository.setting, &context.modules_dir);
 
         Self {
+            config_path: &context.config_path,
             modules: &context.modules,
             original: Arc::new(update),
         }
     }
 
     pub async fn update_all(&self, module_ids: &[String]) {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return,
+        };
+
+        if !Validate::new(self.config_path).is_valid() {
+            return;
+        }
+
         let modules: Vec<Arc<Module>> = if module_ids.is_empty() {
             self.modules.iter().map(|m| m.to_owned()).collect()
         } else {
@@ -333,7 +934,7 @@ impl<'u> UpdateWrapper<'u> {
             .into_iter()
             .map(|m| {
                 let original = self.original.to_owned();
-                tokio::spawn(async move { original.update(&m).await })
+                tokio::spawn(async move { original.update_locked(&m).await })
             })
             .collect();
 
@@ -5,12 +5,12 @@ use std::sync::Arc;
 use chrono::Utc;
 use walkdir::WalkDir;
 
-use mrepo_model::config::{Module, Repository};
+use mrepo_model::config::{Module, Repository, RootSupport};
 use mrepo_model::modules;
 use mrepo_model::track::{Track, Version};
 
-use crate::util::{FileUtil, Json};
-use crate::{constant, Context};
+use crate::util::{FileUtil, Json, LocalModule, Lock, Root};
+use crate::{constant, Context, Validate};
 
 pub struct Upgrade {
     repository: Repository,
@@ -27,7 +27,7 @@ impl Upgrade {
         }
     }
 
-    fn generate_version(&self, id: &String, origin: &Version) -> modules::Version {
+    fn generate_version(&self, id: &String, origin: &Version) -> Option<modules::Version> {
         let module_dir = self.modules_dir.join(id);
         let base_url = &self.repository.setting.base_url;
         let module_path = format!("{}/{}", constant::MODULES_DIR, id);
@@ -36,33 +36,107 @@ impl Upgrade {
         let zip_url = if zip_file.is_file() && zip_file.exists() {
             format!("{base_url}/{module_path}/{}", origin.zip_file)
         } else { 
-            String::new()
+            tracing::warn!(
+                target: "Upgrade::generate_version",
+                %id,
+                version_code = %origin.version_code,
+                file = %origin.zip_file,
+                "Zip not found, version skipped"
+            );
+            return None;
         };
         
         let changelog = module_dir.join(&origin.changelog);
         let changelog = if changelog.is_file() && changelog.exists() {
             format!("{base_url}/{module_path}/{}", origin.changelog)
         } else {
+            if !origin.changelog.is_empty() {
+                tracing::warn!(
+                    target: "Upgrade::generate_version",
+                    %id,
+                    version_code = %origin.version_code,
+                    file = %origin.changelog,
+                    "Changelog not found"
+                );
+            }
             String::new()
         };
 
-        modules::Version {
+        Some(modules::Version {
             timestamp: origin.timestamp,
             version: origin.version.to_owned(),
             version_code: origin.version_code,
             zip_url,
             changelog,
+            yanked: origin.yanked,
+            yanked_reason: origin.yanked_reason.to_owned(),
+        })
+    }
+
+    fn is_published(&self, origin: &Module, version: &Version) -> bool {
+        if version.withdrawn {
+            return false;
+        }
+
+        match origin.setting.pin_version_code {
+            Some(pin) => version.version_code <= pin,
+            None => true,
         }
     }
 
-    fn generate_module(&self, track: Track, origin: &Module) -> modules::Module {
-        let versions = track
+    fn detect_root(&self, origin: &Module, version: Option<&Version>) -> Option<RootSupport> {
+        let zip_file = version.map(|v| self.modules_dir.join(&origin.id).join(&v.zip_file));
+        let mut root = match zip_file.and_then(Root::detect) {
+            Some(r) => r,
+            None if !origin.setting.root.is_empty() => RootSupport::default(),
+            None => return None,
+        };
+
+        root.apply(&origin.setting.root);
+        Some(root)
+    }
+
+    fn generate_module(&self, track: Track, origin: &Module) -> Option<modules::Module> {
+        let versions: Vec<modules::Version> = track
             .versions
             .iter()
-            .map(|v| self.generate_version(&origin.id, v))
+            .filter(|v| self.is_published(origin, v))
+            .filter_map(|v| self.generate_version(&origin.id, v))
             .collect();
 
-        modules::Module::build(track.module, origin.metadata.to_owned(), versions)
+        let version_code = match versions.first() {
+            Some(latest) => latest.version_code,
+            None => {
+                tracing::warn!(target: "Upgrade::generate_module", id = %origin.id, "No version published");
+                return None;
+            }
+        };
+
+        let latest = track
+            .versions
+            .iter()
+            .find(|v| self.is_published(origin, v) && v.version_code == version_code);
+
+        let mut module = track.module;
+        if let Some(latest) = latest.filter(|v| v.version_code != module.version_code) {
+            let zip_file = self.modules_dir.join(&origin.id).join(&latest.zip_file);
+            match LocalModule::read_zip(&zip_file) {
+                Some(prop) => module = prop,
+                None => {
+                    module.version = latest.version.to_owned();
+                    module.version_code = latest.version_code;
+                }
+            }
+        }
+
+        let root = self.detect_root(origin, latest);
+
+        Some(modules::Module::build(
+            module,
+            origin.metadata.to_owned(),
+            root,
+            versions,
+        ))
     }
 
     pub fn generate_modules(&self, origins: &[Arc<Module>]) -> Vec<modules::Module> {
@@ -85,7 +159,9 @@ impl Upgrade {
                 }
             };
 
-            modules.push(self.generate_module(track, origin));
+            if let Some(module) = self.generate_module(track, origin) {
+                modules.push(module);
+            }
         }
 
         modules
@@ -96,6 +172,19 @@ impl Upgrade {
         modules: &modules::Modules,
         path: P,
         pretty: bool,
+    ) -> bool {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return false,
+        };
+
+        Self::write_modules_locked(modules, path, pretty)
+    }
+
+    fn write_modules_locked<P: AsRef<Path>>(
+        modules: &modules::Modules,
+        path: P,
+        pretty: bool,
     ) -> bool {
         match modules.to_file(path, pretty) {
             Ok(_) => true,
@@ -107,6 +196,15 @@ impl Upgrade {
     }
 
     pub async fn remove_unkonwn_path(&self, modules: &[Arc<Module>]) -> Vec<PathBuf> {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return Vec::new(),
+        };
+
+        self.remove_unknown_locked(modules)
+    }
+
+    fn remove_unknown_locked(&self, modules: &[Arc<Module>]) -> Vec<PathBuf> {
         let mut paths = Vec::new();
         let module_ids: Vec<String> = modules.iter().map(|m| m.id.to_owned()).collect();
         let walk_dir = WalkDir::new(&self.modules_dir).min_depth(1).max_depth(1);
@@ -132,6 +230,7 @@ impl Upgrade {
 }
 
 pub struct UpgradeWrapper<'u> {
+    config_path: &'u Path,
     modules: &'u Vec<Arc<Module>>,
     original: Arc<Upgrade>,
 }
@@ -141,12 +240,22 @@ impl<'u> UpgradeWrapper<'u> {
         let upgrade = Upgrade::new(&context.repository, &context.json_dir, &context.modules_dir);
 
         Self {
+            config_path: &context.config_path,
             modules: &context.modules,
             original: Arc::new(upgrade),
         }
     }
 
     pub async fn generate_index_to<P: AsRef<Path>>(&self, path: P, pretty: bool) {
+        let _lock = match Lock::hold(&self.modules_dir) {
+            Some(l) => l,
+            None => return,
+        };
+
+        if !Validate::new(self.config_path).is_valid() {
+            return;
+        }
+
         let modules_new = modules::Modules {
             name: self.repository.name.to_owned(),
             timestamp: Utc::now().timestamp_millis(),
@@ -154,8 +263,8 @@ impl<'u> UpgradeWrapper<'u> {
             modules: self.generate_modules(self.modules),
         };
 
-        self.write_modules_to(&modules_new, path, pretty).await;
-        self.remove_unkonwn_path(self.modules).await;
+        Upgrade::write_modules_locked(&modules_new, path, pretty);
+        self.remove_unknown_locked(self.modules);
     }
 
     pub async fn generate_index(&self, pretty: bool) {
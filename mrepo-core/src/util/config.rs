@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Serialize;
+use serde_json::Value;
+use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item, Table};
+
+use mrepo_model::config::{Config, ModuleEntry};
+
+use crate::error::Error;
+use crate::util::{append_extension, write_atomic, StrUtil};
+use crate::{constant, error};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+const EXTENSIONS: [&str; 4] = ["json", "toml", "yaml", "yml"];
+
+impl ConfigFormat {
+    pub fn is_config<P: AsRef<Path>>(path: P) -> bool {
+        let path = path.as_ref();
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| EXTENSIONS.contains(&e))
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    pub fn existing<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+        let dir = dir.as_ref();
+        EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}.{ext}", constant::CONFIG_NAME)))
+            .find(|p| p.exists())
+    }
+
+    pub fn find<P: AsRef<Path>>(dir: P) -> PathBuf {
+        let dir = dir.as_ref();
+        Self::existing(dir).unwrap_or_else(|| dir.join(constant::CONFIG_JSON))
+    }
+
+    pub fn from_slice<T: DeserializeOwned>(&self, v: &[u8]) -> error::Result<T> {
+        match self {
+            ConfigFormat::Json => serde_json::from_slice(v).map_err(Error::json),
+            ConfigFormat::Toml => {
+                let text = String::from_utf8_lossy(v);
+                toml::from_str(&text).map_err(Error::custom)
+            }
+            ConfigFormat::Yaml => serde_norway::from_slice(v).map_err(Error::custom),
+        }
+    }
+
+    pub fn to_string<T: Serialize>(&self, value: &T) -> error::Result<String> {
+        match self {
+            ConfigFormat::Json => serde_json::to_string_pretty(value).map_err(Error::json),
+            ConfigFormat::Toml => toml::to_string_pretty(value).map_err(Error::custom),
+            ConfigFormat::Yaml => serde_norway::to_string(value).map_err(Error::custom),
+        }
+    }
+
+    pub fn expand_value(value: &mut Value, pointer: &str, missing: &mut Vec<(String, String)>) {
+        match value {
+            Value::String(text) => {
+                let mut names = Vec::new();
+                *text = StrUtil::expand_env(text, &mut names);
+                missing.extend(names.into_iter().map(|n| (pointer.to_owned(), n)));
+            }
+            Value::Array(values) => {
+                for (index, v) in values.iter_mut().enumerate() {
+                    Self::expand_value(v, &format!("{pointer}/{index}"), missing);
+                }
+            }
+            Value::Object(map) => {
+                for (key, v) in map.iter_mut() {
+                    let key = key.replace('~', "~0").replace('/', "~1");
+                    Self::expand_value(v, &format!("{pointer}/{key}"), missing);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn expand_env<T: Serialize + DeserializeOwned>(value: &T) -> error::Result<T> {
+        let mut value = serde_json::to_value(value).map_err(Error::json)?;
+        let mut missing = Vec::new();
+        Self::expand_value(&mut value, "", &mut missing);
+
+        if !missing.is_empty() {
+            let missing: Vec<String> = missing
+                .into_iter()
+                .map(|(pointer, name)| format!("`{name}` at {pointer}"))
+                .collect();
+            return Err(Error::custom(format!(
+                "Environment variables not set: {}",
+                missing.join(", ")
+            )));
+        }
+
+        serde_json::from_value(value).map_err(Error::json)
+    }
+
+    pub fn read<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> error::Result<T> {
+        let path = path.as_ref();
+        let format = Self::from_path(path);
+        let v = fs::read(path).map_err(Error::io)?;
+        format
+            .from_slice(&v)
+            .map_err(|e| Error::custom(format!("{}: {e}", path.display())))
+    }
+
+    fn write_text(&self, text: &str, path: &Path) -> error::Result<()> {
+        write_atomic(
+            path,
+            |writer| writer.write_all(text.as_bytes()).map_err(Error::io),
+            |v| self.from_slice::<IgnoredAny>(v).is_ok(),
+        )
+    }
+
+    pub fn write<T: Serialize, P: AsRef<Path>>(value: &T, path: P) -> error::Result<()> {
+        let path = path.as_ref();
+        let format = Self::from_path(path);
+        let text = format.to_string(value)?;
+
+        format.write_text(&text, path)
+    }
+
+    pub fn update<T: Serialize, P: AsRef<Path>>(value: &T, path: P) -> error::Result<()> {
+        let path = path.as_ref();
+        let format = Self::from_path(path);
+        let text = format.to_string(value)?;
+        let old = match fs::read_to_string(path) {
+            Ok(old) => old,
+            Err(_) => return format.write_text(&text, path),
+        };
+
+        match format {
+            ConfigFormat::Json => format.write_text(&text, path),
+            ConfigFormat::Toml => {
+                let mut doc: DocumentMut = old.parse().map_err(Error::custom)?;
+                let new: DocumentMut = text.parse().map_err(Error::custom)?;
+                TomlMerge::table(doc.as_table_mut(), new.as_table().to_owned());
+                TomlMerge::renumber(doc.as_table_mut(), &mut 0);
+
+                format.write_text(&doc.to_string(), path)
+            }
+            ConfigFormat::Yaml => {
+                let commented = old
+                    .lines()
+                    .any(|l| l.trim_start().starts_with('#') || l.contains(" #"));
+                if !commented {
+                    return format.write_text(&text, path);
+                }
+
+                tracing::warn!(
+                    target: "ConfigFormat::update",
+                    ?path,
+                    "Rewriting would drop comments, edit the file to read:\n{text}"
+                );
+                Err(Error::custom(format!(
+                    "{} has comments, apply the change by hand",
+                    path.display()
+                )))
+            }
+        }
+    }
+}
+
+struct TomlMerge;
+
+impl TomlMerge {
+    fn id(table: &Table) -> Option<&str> {
+        table.get("id").and_then(|i| i.as_str())
+    }
+
+    // Empty fields are skipped when serializing, keep them with their comments.
+    fn is_empty(value: &toml_edit::Value) -> bool {
+        match value {
+            toml_edit::Value::String(s) => s.value().is_empty(),
+            toml_edit::Value::Array(a) => a.is_empty(),
+            toml_edit::Value::InlineTable(t) => t.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn same(old: &toml_edit::Value, new: &toml_edit::Value) -> bool {
+        use toml_edit::Value as V;
+
+        match (old, new) {
+            (V::String(a), V::String(b)) => a.value() == b.value(),
+            (V::Integer(a), V::Integer(b)) => a.value() == b.value(),
+            (V::Float(a), V::Float(b)) => a.value() == b.value(),
+            (V::Boolean(a), V::Boolean(b)) => a.value() == b.value(),
+            (V::Datetime(a), V::Datetime(b)) => a.value() == b.value(),
+            (V::Array(a), V::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| Self::same(a, b))
+            }
+            (V::InlineTable(a), V::InlineTable(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(k, a)| b.get(k).is_some_and(|b| Self::same(a, b)))
+            }
+            _ => false,
+        }
+    }
+
+    fn value(old: &mut toml_edit::Value, mut new: toml_edit::Value) {
+        match (old, &mut new) {
+            (toml_edit::Value::InlineTable(old), toml_edit::Value::InlineTable(new)) => {
+                Self::inline_table(old, std::mem::take(new))
+            }
+            (old, new) if !Self::same(old, new) => {
+                *new.decor_mut() = old.decor().to_owned();
+                *old = new.to_owned();
+            }
+            _ => {}
+        }
+    }
+
+    fn inline_table(old: &mut InlineTable, new: InlineTable) {
+        old.retain(|k, v| new.contains_key(k) || Self::is_empty(v));
+        for (key, value) in new {
+            match old.get_mut(&key) {
+                Some(o) => Self::value(o, value),
+                None => {
+                    old.insert(key, value);
+                }
+            }
+        }
+    }
+
+    fn table(old: &mut Table, new: Table) {
+        old.retain(|k, v| new.contains_key(k) || v.as_value().is_some_and(Self::is_empty));
+        for (key, item) in new {
+            match old.get_mut(&key) {
+                Some(o) => Self::item(o, item),
+                None => {
+                    old.insert(&key, item);
+                }
+            }
+        }
+    }
+
+    fn array_of_tables(old: &mut ArrayOfTables, new: ArrayOfTables) {
+        let mut merged = ArrayOfTables::new();
+        for (index, table) in new.into_iter().enumerate() {
+            let id = Self::id(&table).map(str::to_owned);
+            let existing = old.iter().enumerate().find(|(i, t)| match &id {
+                Some(id) => Self::id(t) == Some(id),
+                None => *i == index,
+            });
+
+            match existing {
+                Some((_, t)) => {
+                    let mut t = t.to_owned();
+                    Self::table(&mut t, table);
+                    merged.push(t);
+                }
+                None => merged.push(table),
+            }
+        }
+
+        *old = merged;
+    }
+
+    fn item(old: &mut Item, new: Item) {
+        match (old, new) {
+            (Item::Table(old), Item::Table(new)) => Self::table(old, new),
+            (Item::Table(old), Item::Value(toml_edit::Value::InlineTable(new))) => {
+                Self::table(old, new.into_table())
+            }
+            (Item::Value(toml_edit::Value::InlineTable(old)), Item::Table(new)) => {
+                Self::inline_table(old, new.into_inline_table())
+            }
+            (Item::ArrayOfTables(old), Item::ArrayOfTables(new)) => Self::array_of_tables(old, new),
+            (Item::Value(old), Item::Value(new)) => Self::value(old, new),
+            (old, new) => *old = new,
+        }
+    }
+
+    fn renumber(table: &mut Table, position: &mut usize) {
+        table.set_position(*position);
+        *position += 1;
+
+        for (_, item) in table.iter_mut() {
+            match item {
+                Item::Table(t) => Self::renumber(t, position),
+                Item::ArrayOfTables(a) => {
+                    for t in a.iter_mut() {
+                        Self::renumber(t, position);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub struct ConfigSet {
+    pub path: PathBuf,
+    pub config: Config,
+    pub includes: Vec<(PathBuf, ModuleEntry)>,
+    loaded: Vec<(PathBuf, ModuleEntry)>,
+    removed: Vec<PathBuf>,
+}
+
+impl ConfigSet {
+    pub fn resolve<P: AsRef<Path>>(path: P, include: &[String]) -> error::Result<Vec<PathBuf>> {
+        let base = path.as_ref().parent().unwrap_or(Path::new(""));
+        let mut files = Vec::new();
+
+        for pattern in include {
+            let full = base.join(pattern);
+            let mut found: Vec<PathBuf> = if full.is_dir() {
+                fs::read_dir(&full)
+                    .map_err(Error::io)?
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .collect()
+            } else {
+                let pattern = full.to_string_lossy();
+                glob::glob(&pattern)
+                    .map_err(|e| Error::custom(format!("Invalid include `{pattern}`: {e}")))?
+                    .filter_map(|p| p.ok())
+                    .collect()
+            };
+
+            found.sort();
+            for file in found {
+                if ConfigFormat::is_config(&file) && !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> error::Result<Self> {
+        let path = path.as_ref();
+        let config: Config = ConfigFormat::read(path)?;
+
+        let mut includes = Vec::new();
+        for file in Self::resolve(path, &config.include)? {
+            let module = ConfigFormat::read(&file)?;
+            includes.push((file, module));
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            config,
+            loaded: includes.to_owned(),
+            includes,
+            removed: Vec::new(),
+        })
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = (&Path, &ModuleEntry)> {
+        let main = self.config.modules.iter().map(|m| (self.path.as_path(), m));
+        let includes = self.includes.iter().map(|(p, m)| (p.as_path(), m));
+        main.chain(includes)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.sources().any(|(_, m)| m.id == id)
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut ModuleEntry> {
+        let main = self.config.modules.iter_mut();
+        let includes = self.includes.iter_mut().map(|(_, m)| m);
+        main.chain(includes).find(|m| m.id == id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let len = self.config.modules.len();
+        self.config.modules.retain(|m| m.id != id);
+        let mut removed = self.config.modules.len() != len;
+
+        let (gone, kept) = self.includes.drain(..).partition(|(_, m)| m.id == id);
+        self.includes = kept;
+        for (path, _) in gone {
+            self.removed.push(path);
+            removed = true;
+        }
+
+        removed
+    }
+
+    pub fn modules(&self) -> error::Result<Vec<ModuleEntry>> {
+        let mut seen: HashMap<&str, &Path> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for (path, module) in self.sources() {
+            if let Some(first) = seen.insert(&module.id, path) {
+                duplicates.push(format!(
+                    "`{}` in {} and {}",
+                    module.id,
+                    first.display(),
+                    path.display()
+                ));
+            }
+        }
+
+        if !duplicates.is_empty() {
+            return Err(Error::custom(format!(
+                "Duplicate module ids: {}",
+                duplicates.join(", ")
+            )));
+        }
+
+        Ok(self.sources().map(|(_, m)| m.to_owned()).collect())
+    }
+
+    pub fn flatten(&self) -> error::Result<Config> {
+        Ok(Config {
+            include: Vec::new(),
+            modules: self.modules()?,
+            ..self.config.to_owned()
+        })
+    }
+
+    fn write_includes(&self, all: bool) -> error::Result<()> {
+        ConfigFormat::update(&self.config, &self.path)?;
+        for include in &self.includes {
+            if all || !self.loaded.contains(include) {
+                ConfigFormat::update(&include.1, &include.0)?;
+            }
+        }
+
+        for path in &self.removed {
+            fs::remove_file(path).map_err(Error::io)?;
+            fs::remove_file(append_extension(path, constant::BAK_EXT)).ok();
+        }
+
+        Ok(())
+    }
+
+    pub fn write(&self) -> error::Result<()> {
+        self.write_includes(false)
+    }
+
+    pub fn write_all(&self) -> error::Result<()> {
+        self.write_includes(true)
+    }
+}
@@ -1,5 +1,8 @@
-use std::fs;
+use std::fs::File;
 use std::path::Path;
+use std::{fs, io};
+
+use sha2::{Digest, Sha256};
 
 use crate::util::str::StrUtil;
 
@@ -38,8 +41,19 @@ impl FileUtil {
             return false;
         }
 
+        fn inner(from: &Path, to: &Path) -> anyhow::Result<()> {
+            if let Some(parent) = to.parent() {
+                if !parent.exists() {
+                    fs::create_dir_all(parent)?
+                }
+            }
+
+            fs::rename(from, to)?;
+            Ok(())
+        }
+
         tracing::debug!(target: "FileUtil::rename", ?from, ?to);
-        if let Err(error) = fs::rename(from, to) {
+        if let Err(error) = inner(from, to) {
             tracing::error!(target: "FileUtil::rename", ?to, ?error);
             false
         } else {
@@ -47,6 +61,36 @@ impl FileUtil {
         }
     }
 
+    pub fn sha256<P: AsRef<Path>>(path: P) -> Option<String> {
+        fn inner(path: &Path) -> anyhow::Result<String> {
+            let mut file = File::open(path)?;
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher)?;
+            Ok(hex::encode(hasher.finalize()))
+        }
+
+        let path = path.as_ref();
+        if !path.is_file() {
+            return None;
+        }
+
+        match inner(path) {
+            Ok(v) => Some(v),
+            Err(error) => {
+                tracing::error!(target: "FileUtil::sha256", ?path, ?error);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    pub fn size<P: AsRef<Path>>(path: P) -> u64 {
+        match fs::metadata(path) {
+            Ok(m) if m.is_file() => m.len(),
+            _ => 0,
+        }
+    }
+
     #[inline]
     pub fn is_html<P: AsRef<Path>>(path: P) -> bool {
         if let Ok(text) = fs::read_to_string(path) {
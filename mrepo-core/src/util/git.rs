@@ -1,9 +1,10 @@
+use std::cmp::Reverse;
 use std::path::Path;
 use std::{env, fs};
 
 use chrono::{DateTime, Utc};
-use git2::build::RepoBuilder;
-use git2::{Cred, FetchOptions, RemoteCallbacks, Repository};
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{Cred, Direction, FetchOptions, Remote, RemoteCallbacks, Repository};
 use once_cell::sync::Lazy;
 
 use crate::constant;
@@ -30,6 +31,51 @@ impl<'g> Git {
         DateTime::from_timestamp(commit.time().seconds(), 0)
     }
 
+    pub fn tags(repository: &Repository) -> Vec<(String, DateTime<Utc>)> {
+        let names = match repository.tag_names(None) {
+            Ok(names) => names,
+            Err(error) => {
+                tracing::error!(target: "Git::tags", path = ?repository.path(), ?error);
+                return Vec::new();
+            }
+        };
+
+        let mut tags: Vec<(String, DateTime<Utc>)> = names
+            .iter()
+            .flatten()
+            .filter_map(|name| {
+                let object = repository
+                    .revparse_single(&format!("refs/tags/{name}"))
+                    .ok()?;
+                let commit = object.peel_to_commit().ok()?;
+                let time = DateTime::from_timestamp(commit.time().seconds(), 0)?;
+                Some((name.to_owned(), time))
+            })
+            .collect();
+
+        tags.sort_by_key(|(_, time)| Reverse(*time));
+        tags
+    }
+
+    pub fn checkout(repository: &Repository, tag: &str) -> bool {
+        fn inner(repository: &Repository, tag: &str) -> anyhow::Result<()> {
+            let object = repository.revparse_single(&format!("refs/tags/{tag}"))?;
+            let commit = object.peel_to_commit()?;
+            let mut builder = CheckoutBuilder::new();
+            repository.checkout_tree(commit.as_object(), Some(builder.force()))?;
+            repository.set_head_detached(commit.id())?;
+            Ok(())
+        }
+
+        match inner(repository, tag) {
+            Ok(_) => true,
+            Err(error) => {
+                tracing::error!(target: "Git::checkout", path = ?repository.path(), %tag, ?error);
+                false
+            }
+        }
+    }
+
     #[inline]
     pub async fn try_clone<P: AsRef<Path>>(url: &str, path: P) -> Option<DateTime<Utc>> {
         match Self::clone(url, path).await {
@@ -38,7 +84,7 @@ impl<'g> Git {
         }
     }
 
-    fn builder() -> RepoBuilder<'g> {
+    fn callbacks() -> RemoteCallbacks<'g> {
         let mut callbacks = RemoteCallbacks::new();
         if !SSH_PRIVATE_KEY.is_empty() {
             callbacks.credentials(|_url, username, _types| {
@@ -46,14 +92,44 @@ impl<'g> Git {
             });
         }
 
+        callbacks
+    }
+
+    fn fetch_options() -> FetchOptions<'g> {
         let mut options = FetchOptions::new();
-        options.remote_callbacks(callbacks);
+        options.remote_callbacks(Self::callbacks());
+        options
+    }
 
+    fn builder() -> RepoBuilder<'g> {
         let mut builder = RepoBuilder::new();
-        builder.fetch_options(options);
+        builder.fetch_options(Self::fetch_options());
         builder
     }
 
+    pub async fn ls_remote(url: &str) -> Option<String> {
+        fn inner(url: &str) -> anyhow::Result<String> {
+            let mut remote = Remote::create_detached(url)?;
+            let connection = remote.connect_auth(Direction::Fetch, Some(Git::callbacks()), None)?;
+            let head = connection
+                .list()?
+                .iter()
+                .find(|h| h.name() == "HEAD")
+                .ok_or_else(|| anyhow::anyhow!("No HEAD found"))?;
+
+            Ok(head.oid().to_string())
+        }
+
+        tracing::debug!(target: "Git::ls_remote", %url);
+        match inner(url) {
+            Ok(oid) => Some(oid),
+            Err(error) => {
+                tracing::error!(target: "Git::ls_remote", %url, ?error);
+                None
+            }
+        }
+    }
+
     pub async fn clone<P: AsRef<Path>>(url: &str, path: P) -> Option<Repository> {
         fn inner(url: &str, path: &Path) -> anyhow::Result<Repository> {
             if let Some(parent) = path.parent() {
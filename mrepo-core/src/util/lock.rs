@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::{fs, process};
+
+use once_cell::sync::Lazy;
+
+use crate::error::Error;
+use crate::{constant, error};
+
+// File locks conflict between handles of the same process, so a lock already
+// held here is shared instead of taken again.
+static HELD: Lazy<Mutex<HashMap<PathBuf, Weak<Held>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Held {
+    file: File,
+    path: PathBuf,
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        tracing::debug!(target: "Lock::release", path = ?self.path);
+        fs::remove_file(&self.path).ok();
+        self.file.unlock().ok();
+    }
+}
+
+pub struct Lock {
+    _held: Arc<Held>,
+}
+
+impl Lock {
+    pub fn acquire<P: AsRef<Path>>(path: P, wait: bool) -> error::Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent).map_err(Error::io)?;
+            }
+        }
+
+        let mut file = Self::open(path)?;
+        let key = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(h) = held.get(&key).and_then(Weak::upgrade) {
+            return Ok(Self { _held: h });
+        }
+
+        loop {
+            match file.try_lock() {
+                Ok(_) => {}
+                Err(TryLockError::WouldBlock) if wait => {
+                    tracing::info!(target: "Lock::acquire", ?path, "Waiting for another instance");
+                    file.lock().map_err(Error::io)?;
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(Error::custom(format!(
+                        "Working directory is locked by another instance ({})",
+                        path.display()
+                    )));
+                }
+                Err(TryLockError::Error(error)) => return Err(Error::io(error)),
+            }
+
+            // The previous holder removes the file on release, so a lock taken
+            // on a file that is no longer at `path` has to be taken again.
+            if Self::is_current(&file, path) {
+                break;
+            }
+
+            file = Self::open(path)?;
+        }
+
+        file.set_len(0).map_err(Error::io)?;
+        write!(file, "{}", process::id()).map_err(Error::io)?;
+
+        tracing::debug!(target: "Lock::acquire", ?path);
+        let h = Arc::new(Held {
+            file,
+            path: path.to_owned(),
+        });
+        held.retain(|_, h| h.strong_count() > 0);
+        held.insert(key, Arc::downgrade(&h));
+
+        Ok(Self { _held: h })
+    }
+
+    fn open(path: &Path) -> error::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(Error::io)
+    }
+
+    #[cfg(unix)]
+    fn is_current(file: &File, path: &Path) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        match (file.metadata(), fs::metadata(path)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn is_current(_file: &File, path: &Path) -> bool {
+        path.exists()
+    }
+
+    pub fn path<P: AsRef<Path>>(modules_dir: P) -> PathBuf {
+        let working_dir = modules_dir.as_ref().parent().unwrap_or(Path::new(""));
+        working_dir
+            .join(constant::JSON_DIR)
+            .join(constant::LOCK_FILE)
+    }
+
+    pub fn hold<P: AsRef<Path>>(modules_dir: P) -> Option<Self> {
+        let path = Self::path(modules_dir);
+        match Self::acquire(&path, false) {
+            Ok(lock) => Some(lock),
+            Err(error) => {
+                tracing::error!(target: "Lock::acquire", ?path, ?error);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use mrepo_model::modules::{Module, Modules, Version};
+
+use crate::util::Request;
+
+pub struct Mirror;
+
+impl Mirror {
+    pub fn split<'a>(provider: &'a str, id: &'a str) -> (&'a str, &'a str) {
+        match provider.split_once('#') {
+            Some((url, upstream)) if !upstream.is_empty() => (url, upstream),
+            Some((url, _)) => (url, id),
+            None => (provider, id),
+        }
+    }
+
+    pub async fn load(provider: &str, id: &str) -> Option<Module> {
+        let (url, upstream) = Self::split(provider, id);
+        let modules: Modules = Request::load_json(url).await?;
+
+        let module = modules.modules.into_iter().find(|m| m.id == upstream);
+        if module.is_none() {
+            tracing::error!(target: "Mirror::load", %url, id = %upstream, "Module not found in upstream");
+        }
+
+        module
+    }
+
+    pub fn latest(module: &Module) -> Option<&Version> {
+        module.versions.first()
+    }
+}
@@ -1,26 +1,39 @@
 use std::fs;
 use std::fs::File;
-use std::path::Path;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 
-use serde::de::DeserializeOwned;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde::Serialize;
 
+pub use config::{ConfigFormat, ConfigSet};
 pub use file::FileUtil;
 #[cfg(feature = "git")]
 pub use git::Git;
+pub use lock::Lock;
+pub use mirror::Mirror;
 pub use module::LocalModule;
 pub use request::Request;
+pub use root::Root;
+pub use span::JsonSpan;
 pub use str::StrUtil;
+pub use template::Templates;
 
-use crate::error;
 use crate::error::Error;
+use crate::{constant, error};
 
+mod config;
 mod file;
 #[cfg(feature = "git")]
 mod git;
+mod lock;
+mod mirror;
 mod module;
 mod request;
+mod root;
+mod span;
 mod str;
+mod template;
 
 pub trait Json: Sized {
     fn from_file<P: AsRef<Path>>(p: P) -> error::Result<Self>;
@@ -30,11 +43,76 @@ pub trait Json: Sized {
     fn to_string_pretty(&self) -> error::Result<String>;
 }
 
+pub(crate) fn append_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".");
+    name.push(ext);
+    path.with_file_name(name)
+}
+
+pub(crate) fn read_recover<T, F>(path: &Path, parse: F) -> error::Result<T>
+where
+    F: Fn(&[u8]) -> error::Result<T>,
+{
+    let result = fs::read(path).map_err(Error::io).and_then(|v| parse(&v));
+
+    let error = match result {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let bak = append_extension(path, constant::BAK_EXT);
+    if !path.exists() || !bak.exists() {
+        return Err(error);
+    }
+
+    let v = fs::read(&bak).map_err(Error::io)?;
+    let value = parse(&v)?;
+    tracing::warn!(target: "util::read_recover", ?path, ?error, "Recovered from backup");
+    Ok(value)
+}
+
+pub(crate) fn write_atomic<W, V>(path: &Path, write: W, is_valid: V) -> error::Result<()>
+where
+    W: FnOnce(&mut BufWriter<File>) -> error::Result<()>,
+    V: Fn(&[u8]) -> bool,
+{
+    let tmp = append_extension(path, constant::TMP_EXT);
+    let bak = append_extension(path, constant::BAK_EXT);
+
+    let write_tmp = || -> error::Result<()> {
+        let file = File::create(&tmp).map_err(Error::io)?;
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+
+        let file = writer.into_inner().map_err(|e| Error::io(e.into_error()))?;
+        file.sync_all().map_err(Error::io)
+    };
+
+    if let Err(error) = write_tmp() {
+        fs::remove_file(&tmp).ok();
+        return Err(error);
+    }
+
+    if let Ok(v) = fs::read(path) {
+        if is_valid(&v) {
+            let bak_tmp = append_extension(&bak, constant::TMP_EXT);
+            fs::write(&bak_tmp, v).map_err(Error::io)?;
+            fs::rename(&bak_tmp, &bak).map_err(Error::io)?;
+        }
+    }
+
+    fs::rename(&tmp, path).map_err(Error::io)?;
+    if let Some(parent) = path.parent().and_then(|p| File::open(p).ok()) {
+        parent.sync_all().ok();
+    }
+
+    Ok(())
+}
+
 impl<T: DeserializeOwned + Serialize> Json for T {
     fn from_file<P: AsRef<Path>>(p: P) -> error::Result<Self> {
-        let v = fs::read(p).map_err(Error::io)?;
-        let value = Self::from_slice(&v)?;
-        Ok(value)
+        read_recover(p.as_ref(), Self::from_slice)
     }
 
     #[inline]
@@ -43,15 +121,18 @@ impl<T: DeserializeOwned + Serialize> Json for T {
     }
 
     fn to_file<P: AsRef<Path>>(&self, p: P, pretty: bool) -> error::Result<()> {
-        let file = File::create(p).map_err(Error::io)?;
-        if pretty {
-            serde_json::to_writer_pretty(file, self)
-        } else {
-            serde_json::to_writer(file, self)
-        }
-        .map_err(Error::json)?;
+        let write = |writer: &mut BufWriter<File>| {
+            if pretty {
+                serde_json::to_writer_pretty(writer, self)
+            } else {
+                serde_json::to_writer(writer, self)
+            }
+            .map_err(Error::json)
+        };
 
-        Ok(())
+        write_atomic(p.as_ref(), write, |v| {
+            serde_json::from_slice::<IgnoredAny>(v).is_ok()
+        })
     }
 
     #[inline]
@@ -7,6 +7,8 @@ use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipArchive};
 
+use serde::de::DeserializeOwned;
+
 use mrepo_model::origin::Module;
 
 use crate::constant;
@@ -14,10 +16,26 @@ use crate::constant;
 pub struct LocalModule;
 
 impl LocalModule {
+    fn parse(bytes: &[u8]) -> anyhow::Result<Module> {
+        let mut module: Module = serde_prop::from_slice(bytes)?;
+        module.prop = serde_prop::from_slice(bytes)?;
+        Ok(module)
+    }
+
+    fn read_zip_bytes(path: &Path) -> anyhow::Result<Vec<u8>> {
+        let file = File::open(path)?;
+        let mut archive = ZipArchive::new(file)?;
+        let mut zip = archive.by_name(constant::MODULE_PROP)?;
+
+        let mut bytes = Vec::new();
+        zip.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
     pub fn read_prop<P: AsRef<Path>>(path: P) -> Option<Module> {
         fn inner(path: &Path) -> anyhow::Result<Module> {
             let bytes = fs::read(path)?;
-            Ok(serde_prop::from_slice(&bytes)?)
+            LocalModule::parse(&bytes)
         }
 
         let path = path.as_ref();
@@ -96,15 +114,30 @@ impl LocalModule {
 
     pub fn read_zip<P: AsRef<Path>>(path: P) -> Option<Module> {
         fn inner(path: &Path) -> anyhow::Result<Module> {
-            let file = File::open(path)?;
-            let mut archive = ZipArchive::new(file)?;
-            let mut zip = archive.by_name(constant::MODULE_PROP)?;
+            let bytes = LocalModule::read_zip_bytes(path)?;
+            LocalModule::parse(&bytes)
+        }
+
+        let path = path.as_ref();
+        if !path.exists() {
+            return None;
+        }
 
-            let mut bytes = Vec::new();
-            zip.read_to_end(&mut bytes)?;
+        tracing::debug!(target: "LocalModule::read_zip", ?path);
+        match inner(path) {
+            Ok(m) => Some(m),
+            Err(error) => {
+                tracing::error!(target: "LocalModule::read_zip", ?path, ?error);
+                None
+            }
+        }
+    }
 
-            let module = serde_prop::from_slice(&bytes)?;
-            Ok(module)
+    pub fn read_zip_as<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Option<T> {
+        fn inner<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+            let bytes = LocalModule::read_zip_bytes(path)?;
+            let value = serde_prop::from_slice(&bytes)?;
+            Ok(value)
         }
 
         let path = path.as_ref();
@@ -114,7 +147,7 @@ impl LocalModule {
 
         tracing::debug!(target: "LocalModule::read_zip", ?path);
         match inner(path) {
-            Ok(m) => Some(m),
+            Ok(v) => Some(v),
             Err(error) => {
                 tracing::error!(target: "LocalModule::read_zip", ?path, ?error);
                 None
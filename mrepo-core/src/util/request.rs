@@ -1,12 +1,13 @@
-use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use std::{env, fs};
 
 use chrono::{DateTime, Utc};
-use reqwest::header::{HeaderMap, LAST_MODIFIED};
+use reqwest::header::{HeaderMap, ACCEPT, LAST_MODIFIED, USER_AGENT};
 use reqwest::{Response, StatusCode};
 
+use crate::constant;
 use crate::util::Json;
 
 pub struct Request {
@@ -20,7 +21,31 @@ impl Request {
             return None;
         }
 
-        match reqwest::get(url).await {
+        Self::build(url, reqwest::get(url).await)
+    }
+
+    pub async fn head(url: &str) -> Option<Self> {
+        if url.is_empty() {
+            return None;
+        }
+
+        Self::build(url, reqwest::Client::new().head(url).send().await)
+    }
+
+    pub async fn github(url: &str) -> Option<Self> {
+        let mut builder = reqwest::Client::new()
+            .get(url)
+            .header(USER_AGENT, env!("CARGO_PKG_NAME"))
+            .header(ACCEPT, "application/vnd.github+json");
+        if let Ok(token) = env::var(constant::GITHUB_TOKEN) {
+            builder = builder.bearer_auth(token);
+        }
+
+        Self::build(url, builder.send().await)
+    }
+
+    fn build(url: &str, result: reqwest::Result<Response>) -> Option<Self> {
+        match result {
             Ok(response) => {
                 let status = response.status();
                 if status == StatusCode::OK {
@@ -0,0 +1,137 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use zip::ZipArchive;
+
+use mrepo_model::config::{RootSolution, RootSupport};
+use mrepo_model::origin::Module;
+
+use crate::util::LocalModule;
+
+static VAR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{?(KSU|APATCH)\}?\b").unwrap());
+static ABORT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\babort\b").unwrap());
+static NEGATED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"!=|-z\s|!\s*\["#).unwrap());
+
+const CUSTOMIZE_SH: &str = "customize.sh";
+const COMMON_FILES: [&str; 6] = [
+    "post-fs-data.sh",
+    "service.sh",
+    "system.prop",
+    "sepolicy.rule",
+    "action.sh",
+    "customize.sh",
+];
+const COMMON_DIRS: [&str; 2] = ["system/", "zygisk/"];
+const KSU_FILES: [&str; 2] = ["post-mount.sh", "boot-completed.sh"];
+const KSU_DIRS: [&str; 1] = ["webroot/"];
+
+pub struct Root;
+
+impl Root {
+    fn solution(var: &str) -> Option<RootSolution> {
+        match var {
+            "KSU" => Some(RootSolution::KernelSU),
+            "APATCH" => Some(RootSolution::APatch),
+            _ => None,
+        }
+    }
+
+    fn check_files(names: &[String], support: &mut RootSupport) {
+        let has = |files: &[&str], dirs: &[&str]| {
+            names
+                .iter()
+                .any(|n| files.contains(&n.as_str()) || dirs.iter().any(|d| n.starts_with(d)))
+        };
+
+        if has(&KSU_FILES, &KSU_DIRS) && !has(&COMMON_FILES, &COMMON_DIRS) {
+            support.magisk = false;
+        }
+    }
+
+    fn check_script(script: &str, support: &mut RootSupport) {
+        let lines: Vec<&str> = script.lines().map(str::trim).collect();
+        for (i, line) in lines.iter().enumerate() {
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let guarded: Vec<RootSolution> = VAR_RE
+                .captures_iter(line)
+                .filter_map(|c| Self::solution(&c[1]))
+                .collect();
+            if guarded.is_empty() {
+                continue;
+            }
+
+            let aborts = ABORT_RE.is_match(line)
+                || (line.starts_with("if ")
+                    && lines[i + 1..]
+                        .iter()
+                        .take_while(|l| !l.starts_with("fi") && !l.starts_with("el"))
+                        .any(|l| ABORT_RE.is_match(l)));
+            if !aborts {
+                continue;
+            }
+
+            if NEGATED_RE.is_match(line) {
+                RootSolution::ALL
+                    .iter()
+                    .filter(|s| !guarded.contains(s))
+                    .for_each(|s| support.set(*s, false));
+            } else {
+                guarded.iter().for_each(|s| support.set(*s, false));
+            }
+        }
+    }
+
+    fn check_prop(module: &Module, support: &mut RootSupport) {
+        for solution in RootSolution::ALL {
+            if let Some(supported) = module
+                .get_prop(solution.as_str())
+                .and_then(|v| v.parse().ok())
+            {
+                support.set(solution, supported);
+            }
+        }
+    }
+
+    pub fn detect<P: AsRef<Path>>(path: P) -> Option<RootSupport> {
+        fn inner(path: &Path) -> anyhow::Result<RootSupport> {
+            let file = File::open(path)?;
+            let mut archive = ZipArchive::new(file)?;
+            let names: Vec<String> = archive.file_names().map(|n| n.to_owned()).collect();
+
+            let mut support = RootSupport::default();
+            Root::check_files(&names, &mut support);
+
+            if let Ok(mut zip) = archive.by_name(CUSTOMIZE_SH) {
+                let mut script = String::new();
+                zip.read_to_string(&mut script)?;
+                Root::check_script(&script, &mut support);
+            }
+
+            if let Some(module) = LocalModule::read_zip(path) {
+                Root::check_prop(&module, &mut support);
+            }
+
+            Ok(support)
+        }
+
+        let path = path.as_ref();
+        if !path.exists() {
+            return None;
+        }
+
+        tracing::debug!(target: "Root::detect", ?path);
+        match inner(path) {
+            Ok(s) => Some(s),
+            Err(error) => {
+                tracing::error!(target: "Root::detect", ?path, ?error);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::util::StrUtil;
+
+pub struct JsonSpan {
+    text: String,
+    offsets: HashMap<String, usize>,
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    offsets: HashMap<String, usize>,
+}
+
+impl Scanner<'_> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn string(&mut self) -> String {
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            match b {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+
+        let end = self.pos.min(self.bytes.len());
+        self.pos += 1;
+        String::from_utf8_lossy(&self.bytes[start..end]).into_owned()
+    }
+
+    fn value(&mut self, pointer: String) {
+        self.skip_ws();
+        self.offsets.insert(pointer.to_owned(), self.pos);
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b'"') => {}
+                        Some(_) => {
+                            self.pos += 1;
+                            if self.bytes[self.pos - 1] == b'}' {
+                                break;
+                            }
+                            continue;
+                        }
+                        None => break,
+                    }
+
+                    let key = self.string().replace('~', "~0").replace('/', "~1");
+                    self.skip_ws();
+                    if self.peek() == Some(b':') {
+                        self.pos += 1;
+                    }
+                    self.value(format!("{pointer}/{key}"));
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(b',') => self.pos += 1,
+                        Some(_) => {
+                            self.value(format!("{pointer}/{index}"));
+                            index += 1;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            Some(b'"') => {
+                self.string();
+            }
+            Some(_) => {
+                while let Some(b) = self.peek() {
+                    if b.is_ascii_whitespace() || matches!(b, b',' | b']' | b'}') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+impl JsonSpan {
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        let text = text.into();
+        let mut scanner = Scanner {
+            bytes: text.as_bytes(),
+            pos: 0,
+            offsets: HashMap::new(),
+        };
+        scanner.value(String::new());
+
+        let offsets = scanner.offsets;
+        Self { text, offsets }
+    }
+
+    pub fn position(&self, pointer: &str) -> (usize, usize) {
+        let mut pointer = pointer;
+        let offset = loop {
+            if let Some(offset) = self.offsets.get(pointer) {
+                break *offset;
+            }
+
+            match pointer.rfind('/') {
+                Some(i) => pointer = &pointer[..i],
+                None => break 0,
+            }
+        };
+
+        StrUtil::line_column(&self.text, offset)
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::env;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -6,12 +9,109 @@ pub struct StrUtil;
 static HTML_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"<html\s*>|<head\s*>|<body\s*>|<!doctype\s*html\s*>").unwrap());
 
+static ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9._-]+$").unwrap());
+
+static ENV_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\$\{|\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}").unwrap());
+
+static VAR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\$?)\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+
+static MAJOR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*[vV]?(\d+)").unwrap());
+
 impl StrUtil {
     #[inline]
     pub fn is_html(text: &str) -> bool {
         HTML_RE.find(text).is_some()
     }
 
+    #[inline]
+    pub fn is_module_id(id: &str) -> bool {
+        ID_RE.is_match(id)
+    }
+
+    pub fn expand_env(text: &str, missing: &mut Vec<String>) -> String {
+        ENV_RE
+            .replace_all(text, |c: &regex::Captures| {
+                let name = match c.get(1) {
+                    Some(n) => n.as_str(),
+                    None => return "${".to_owned(),
+                };
+
+                match (env::var(name).ok(), c.get(2)) {
+                    (Some(v), None) => v,
+                    (Some(v), Some(_)) if !v.is_empty() => v,
+                    (_, Some(default)) => default.as_str().to_owned(),
+                    (None, None) => {
+                        missing.push(name.to_owned());
+                        String::new()
+                    }
+                }
+            })
+            .into_owned()
+    }
+
+    pub fn expand_vars(
+        text: &str,
+        vars: &BTreeMap<String, String>,
+        unknown: &mut Vec<String>,
+    ) -> String {
+        VAR_RE
+            .replace_all(text, |c: &regex::Captures| {
+                if !c[1].is_empty() {
+                    return c[0].to_owned();
+                }
+
+                let name = &c[2];
+                match vars.get(name) {
+                    Some(v) => v.to_owned(),
+                    None => {
+                        unknown.push(name.to_owned());
+                        c[0].to_owned()
+                    }
+                }
+            })
+            .into_owned()
+    }
+
+    pub fn line_column(text: &str, offset: usize) -> (usize, usize) {
+        let mut end = offset.min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let before = &text[..end];
+        let line = before.matches('\n').count() + 1;
+        let column = match before.rfind('\n') {
+            Some(i) => before[i + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+
+        (line, column)
+    }
+
+    pub fn get_major_version(version: &str) -> Option<u64> {
+        MAJOR_RE
+            .captures(version)
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse().ok())
+    }
+
+    /// Whether a release `tag` names the tracked `version`, as shown by
+    /// [`StrUtil::get_version_display`].
+    pub fn matches_tag(version: &str, tag: &str) -> bool {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let tag = tag.trim().trim_start_matches(['v', 'V']);
+        if tag.is_empty() {
+            return false;
+        }
+
+        version == tag
+            || version
+                .strip_prefix(tag)
+                .is_some_and(|rest| rest.starts_with(" ("))
+    }
+
     pub fn get_version_display(version: &str, version_code: i64) -> String {
         let re = Regex::new(&format!(r"\(.*?{version_code}.*?\)")).unwrap();
         if re.find(version).is_some() {
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use mrepo_model::config::{
+    Config, Module, ModuleEntry, ModuleMetadata, ModuleSetting, ModuleTemplate,
+};
+
+use crate::error;
+use crate::error::Error;
+use crate::util::StrUtil;
+
+pub struct Templates<'a> {
+    defaults: &'a ModuleTemplate,
+    templates: &'a BTreeMap<String, ModuleTemplate>,
+}
+
+fn first(values: &[&String]) -> String {
+    values
+        .iter()
+        .find(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+}
+
+impl<'a> Templates<'a> {
+    pub fn new(
+        defaults: &'a ModuleTemplate,
+        templates: &'a BTreeMap<String, ModuleTemplate>,
+    ) -> Self {
+        Self {
+            defaults,
+            templates,
+        }
+    }
+
+    pub fn from_config(config: &'a Config) -> Self {
+        Self::new(&config.defaults, &config.templates)
+    }
+
+    fn metadata(values: &[&ModuleMetadata]) -> ModuleMetadata {
+        ModuleMetadata {
+            license: first(&values.iter().map(|m| &m.license).collect::<Vec<_>>()),
+            homepage: first(&values.iter().map(|m| &m.homepage).collect::<Vec<_>>()),
+            source: first(&values.iter().map(|m| &m.source).collect::<Vec<_>>()),
+            donate: first(&values.iter().map(|m| &m.donate).collect::<Vec<_>>()),
+            support: first(&values.iter().map(|m| &m.support).collect::<Vec<_>>()),
+        }
+    }
+
+    fn setting(values: &[&ModuleSetting]) -> ModuleSetting {
+        ModuleSetting {
+            disabled: values.iter().find_map(|s| s.disabled),
+            keep_size: values.iter().find_map(|s| s.keep_size),
+            retention: values.iter().find_map(|s| s.retention.to_owned()),
+            downgrade: values.iter().find_map(|s| s.downgrade),
+            reupload: values.iter().find_map(|s| s.reupload),
+            pin_version_code: values.iter().find_map(|s| s.pin_version_code),
+            root: values
+                .iter()
+                .rev()
+                .flat_map(|s| s.root.iter().map(|(k, v)| (*k, *v)))
+                .collect(),
+        }
+    }
+
+    pub fn resolve(&self, entry: &ModuleEntry) -> Result<Module, String> {
+        let template = match entry.template.as_str() {
+            "" => None,
+            name => match self.templates.get(name) {
+                Some(t) => Some(t),
+                None => return Err(format!("unknown template `{name}`")),
+            },
+        };
+        let parents: Vec<&ModuleTemplate> = template.into_iter().chain([self.defaults]).collect();
+
+        let kind = entry
+            .kind
+            .or_else(|| parents.iter().find_map(|t| t.kind))
+            .ok_or("kind is not set and not inherited")?;
+
+        let mut vars = BTreeMap::new();
+        for parent in parents.iter().rev() {
+            vars.extend(parent.vars.to_owned());
+        }
+        vars.insert("id".to_owned(), entry.id.to_owned());
+
+        let mut provider = vec![&entry.provider];
+        let mut changelog = vec![&entry.changelog];
+        let mut metadata = vec![&entry.metadata];
+        let mut setting = vec![&entry.setting];
+        for parent in &parents {
+            provider.push(&parent.provider);
+            changelog.push(&parent.changelog);
+            metadata.push(&parent.metadata);
+            setting.push(&parent.setting);
+        }
+
+        let mut unknown = Vec::new();
+        let mut expand = |text: String| StrUtil::expand_vars(&text, &vars, &mut unknown);
+        let metadata = Self::metadata(&metadata);
+        let module = Module {
+            id: entry.id.to_owned(),
+            kind,
+            provider: expand(first(&provider)),
+            changelog: expand(first(&changelog)),
+            metadata: ModuleMetadata {
+                license: expand(metadata.license),
+                homepage: expand(metadata.homepage),
+                source: expand(metadata.source),
+                donate: expand(metadata.donate),
+                support: expand(metadata.support),
+            },
+            setting: Self::setting(&setting),
+        };
+
+        if !unknown.is_empty() {
+            unknown.dedup();
+            return Err(format!("unknown variables `{}`", unknown.join("`, `")));
+        }
+
+        Ok(module)
+    }
+
+    pub fn resolve_all(&self, entries: &[ModuleEntry]) -> error::Result<Vec<Module>> {
+        entries
+            .iter()
+            .map(|e| {
+                self.resolve(e)
+                    .map_err(|msg| Error::custom(format!("Module `{}`: {msg}", e.id)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use mrepo_model::config::{
+    Log, Module, ModuleEntry, ModuleTemplate, ProviderKind, Repository, Retention,
+};
+use mrepo_model::migrate;
+
+use crate::error::Error;
+use crate::util::{ConfigFormat, ConfigSet, JsonSpan, Mirror, StrUtil, Templates};
+use crate::{constant, error};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line != 0 {
+            write!(f, "{}:{}: ", self.line, self.column)?;
+        }
+        if !self.pointer.is_empty() {
+            write!(f, "{}: ", self.pointer)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+struct Diagnostics {
+    file: PathBuf,
+    span: Option<JsonSpan>,
+    unresolved: HashSet<String>,
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    fn push<T: Display>(&mut self, pointer: &str, message: T) {
+        let (line, column) = match &self.span {
+            Some(span) => span.position(pointer),
+            None => (0, 0),
+        };
+        self.items.push(Diagnostic {
+            file: self.file.to_owned(),
+            line,
+            column,
+            pointer: pointer.to_owned(),
+            message: message.to_string(),
+        });
+    }
+
+    fn parse<T: DeserializeOwned>(&mut self, value: &Value, pointer: &str) -> Option<T> {
+        let field = match value.pointer(pointer) {
+            Some(v) => v,
+            None => {
+                let name = pointer.rsplit('/').next().unwrap_or_default();
+                self.push("", format!("missing field `{name}`"));
+                return None;
+            }
+        };
+
+        match serde_json::from_value(field.to_owned()) {
+            Ok(t) => Some(t),
+            Err(error) => {
+                self.push(pointer, error);
+                None
+            }
+        }
+    }
+
+    fn check_retention(&mut self, pointer: &str, retention: &Retention) {
+        let value = &retention.keep_newer_than;
+        if value.is_empty() {
+            return;
+        }
+
+        if let Err(error) = humantime::parse_duration(value) {
+            self.push(
+                &format!("{pointer}/keep_newer_than"),
+                format!("invalid duration `{value}` ({error})"),
+            );
+        }
+    }
+
+    fn check_url(&mut self, pointer: &str, url: &str) {
+        if self.unresolved.contains(pointer) {
+            return;
+        }
+
+        if let Err(error) = Url::parse(url) {
+            self.push(pointer, format!("invalid url `{url}` ({error})"));
+        }
+    }
+}
+
+pub struct Validate {
+    config_path: PathBuf,
+}
+
+impl Validate {
+    pub fn new<P: AsRef<Path>>(config_path: P) -> Self {
+        Self {
+            config_path: PathBuf::from(config_path.as_ref()),
+        }
+    }
+
+    pub fn from_working_dir<P: AsRef<Path>>(working_dir: P) -> Self {
+        let json_dir = working_dir.as_ref().join(constant::JSON_DIR);
+        let config_path = ConfigFormat::find(json_dir);
+
+        Self::new(config_path)
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    fn check_repository(d: &mut Diagnostics, repository: &Repository) {
+        let base_url = &repository.setting.base_url;
+        let pointer = "/repository/setting/base_url";
+        if base_url.ends_with('/') {
+            d.push(pointer, "base_url must not end with `/`");
+        }
+        if !base_url.is_empty() {
+            d.check_url(pointer, base_url);
+        }
+
+        if repository.setting.keep_size == 0 {
+            d.push(
+                "/repository/setting/keep_size",
+                "keep_size must be at least 1",
+            );
+        }
+
+        d.check_retention(
+            "/repository/setting/retention",
+            &repository.setting.retention,
+        );
+    }
+
+    fn check_module(d: &mut Diagnostics, pointer: &str, module: &Module) {
+        if !StrUtil::is_module_id(&module.id) {
+            d.push(
+                &format!("{pointer}/id"),
+                format!("`{}` is not a valid module id", module.id),
+            );
+        }
+
+        let provider = format!("{pointer}/provider");
+        if module.provider.is_empty() {
+            d.push(&provider, "provider must not be empty");
+        } else {
+            match module.kind {
+                ProviderKind::UpdateJson | ProviderKind::ZipUrl => {
+                    d.check_url(&provider, &module.provider)
+                }
+                ProviderKind::Mirror => {
+                    let (url, _) = Mirror::split(&module.provider, &module.id);
+                    d.check_url(&provider, url)
+                }
+                #[cfg(feature = "git")]
+                ProviderKind::Git => {}
+            }
+        }
+
+        if !module.changelog.is_empty() {
+            d.check_url(&format!("{pointer}/changelog"), &module.changelog);
+        }
+
+        if module.setting.keep_size == Some(0) {
+            d.push(
+                &format!("{pointer}/setting/keep_size"),
+                "keep_size must be at least 1",
+            );
+        }
+
+        if let Some(retention) = &module.setting.retention {
+            d.check_retention(&format!("{pointer}/setting/retention"), retention);
+        }
+    }
+
+    fn check_module_value(
+        d: &mut Diagnostics,
+        value: &Value,
+        pointer: &str,
+        templates: &Templates,
+        ids: &mut HashMap<String, (PathBuf, String)>,
+    ) {
+        let kind = value.pointer(&format!("{pointer}/kind"));
+        if cfg!(not(feature = "git")) && kind == Some(&Value::from("git")) {
+            d.push(
+                &format!("{pointer}/kind"),
+                "kind `git` requires a build with the `git` feature",
+            );
+            return;
+        }
+
+        let entry: ModuleEntry = match d.parse(value, pointer) {
+            Some(m) => m,
+            None => return,
+        };
+
+        match ids.get(&entry.id) {
+            Some((file, first)) => {
+                let first = if *file == d.file {
+                    first.to_owned()
+                } else {
+                    format!("{}#{first}", file.display())
+                };
+                d.push(
+                    &format!("{pointer}/id"),
+                    format!("duplicate module id `{}` (first at {first})", entry.id),
+                );
+            }
+            None => {
+                ids.insert(entry.id.to_owned(), (d.file.to_owned(), pointer.to_owned()));
+            }
+        }
+
+        match templates.resolve(&entry) {
+            Ok(module) => Self::check_module(d, pointer, &module),
+            Err(error) => d.push(pointer, error),
+        }
+    }
+
+    fn check_modules(
+        d: &mut Diagnostics,
+        value: &Value,
+        templates: &Templates,
+        ids: &mut HashMap<String, (PathBuf, String)>,
+    ) {
+        let modules = match value.pointer("/modules") {
+            Some(Value::Array(modules)) => modules,
+            Some(_) => {
+                d.push("/modules", "modules must be an array");
+                return;
+            }
+            None => return,
+        };
+
+        for index in 0..modules.len() {
+            let pointer = format!("/modules/{index}");
+            Self::check_module_value(d, value, &pointer, templates, ids);
+        }
+    }
+
+    fn syntax_error<T: Display>(d: &mut Diagnostics, line: usize, column: usize, error: T) {
+        let message = error.to_string();
+        let suffix = format!(" at line {line} column {column}");
+        d.items.push(Diagnostic {
+            file: d.file.to_owned(),
+            line,
+            column,
+            pointer: String::new(),
+            message: message
+                .replace(&suffix, "")
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+
+    fn load(path: &Path) -> error::Result<(Diagnostics, Option<Value>)> {
+        let text = fs::read_to_string(path).map_err(Error::io)?;
+        let format = ConfigFormat::from_path(path);
+        let mut d = Diagnostics {
+            file: path.to_owned(),
+            span: None,
+            unresolved: HashSet::new(),
+            items: Vec::new(),
+        };
+
+        let value: Value = match format {
+            ConfigFormat::Json => match serde_json::from_str(&text) {
+                Ok(v) => v,
+                Err(error) => {
+                    Self::syntax_error(&mut d, error.line(), error.column(), error);
+                    return Ok((d, None));
+                }
+            },
+            ConfigFormat::Toml => match toml::from_str(&text) {
+                Ok(v) => v,
+                Err(error) => {
+                    let (line, column) = match error.span() {
+                        Some(span) => StrUtil::line_column(&text, span.start),
+                        None => (0, 0),
+                    };
+                    Self::syntax_error(&mut d, line, column, error.message());
+                    return Ok((d, None));
+                }
+            },
+            ConfigFormat::Yaml => match serde_norway::from_str(&text) {
+                Ok(v) => v,
+                Err(error) => {
+                    let (line, column) = match error.location() {
+                        Some(l) => (l.line(), l.column()),
+                        None => (0, 0),
+                    };
+                    Self::syntax_error(&mut d, line, column, error);
+                    return Ok((d, None));
+                }
+            },
+        };
+
+        if format == ConfigFormat::Json {
+            d.span = Some(JsonSpan::new(text));
+        }
+
+        let mut value = value;
+        let mut missing = Vec::new();
+        ConfigFormat::expand_value(&mut value, "", &mut missing);
+        for (pointer, name) in missing {
+            d.push(
+                &pointer,
+                format!("environment variable `{name}` is not set"),
+            );
+            d.unresolved.insert(pointer);
+        }
+
+        if !value.is_object() {
+            d.push("", "expected an object");
+            return Ok((d, None));
+        }
+
+        Ok((d, Some(value)))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self.validate() {
+            Ok(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    tracing::error!(target: "Validate::is_valid", file = ?diagnostic.file, "{diagnostic}");
+                }
+
+                diagnostics.is_empty()
+            }
+            Err(error) => {
+                tracing::error!(target: "Validate::is_valid", path = ?self.config_path, ?error);
+                false
+            }
+        }
+    }
+
+    pub fn validate(&self) -> error::Result<Vec<Diagnostic>> {
+        let mut ids = HashMap::new();
+        let (mut d, value) = Self::load(&self.config_path)?;
+        let mut value = match value {
+            Some(v) => v,
+            None => return Ok(d.items),
+        };
+
+        if let Err(error) = migrate::config(&mut value) {
+            d.push("/version", error);
+            return Ok(d.items);
+        }
+
+        d.parse::<Log>(&value, "/log");
+
+        if let Some(repository) = d.parse::<Repository>(&value, "/repository") {
+            Self::check_repository(&mut d, &repository);
+        }
+
+        let mut defaults = ModuleTemplate::default();
+        if value.get("defaults").is_some() {
+            defaults = d.parse(&value, "/defaults").unwrap_or_default();
+        }
+        let mut named = BTreeMap::new();
+        if value.get("templates").is_some() {
+            named = d.parse(&value, "/templates").unwrap_or_default();
+        }
+        let templates = Templates::new(&defaults, &named);
+
+        Self::check_modules(&mut d, &value, &templates, &mut ids);
+
+        let mut files = Vec::new();
+        if value.get("include").is_some() {
+            if let Some(include) = d.parse::<Vec<String>>(&value, "/include") {
+                match ConfigSet::resolve(&self.config_path, &include) {
+                    Ok(f) => files = f,
+                    Err(error) => d.push("/include", error),
+                }
+            }
+        }
+
+        d.items.sort_by_key(|i| (i.line, i.column));
+        let mut items = d.items;
+
+        for file in files {
+            let (mut d, value) = Self::load(&file)?;
+            if let Some(value) = value {
+                Self::check_module_value(&mut d, &value, "", &templates, &mut ids);
+            }
+
+            d.items.sort_by_key(|i| (i.line, i.column));
+            items.extend(d.items);
+        }
+
+        Ok(items)
+    }
+}
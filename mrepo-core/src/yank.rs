@@ -0,0 +1,86 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mrepo_model::track::Track;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::util::{Json, Lock};
+use crate::{constant, error};
+
+pub struct Yank {
+    modules_dir: PathBuf,
+}
+
+impl Yank {
+    pub fn new<P: AsRef<Path>>(modules_dir: P) -> Self {
+        Self {
+            modules_dir: PathBuf::from(modules_dir.as_ref()),
+        }
+    }
+
+    fn modify(&self, id: &str, version_code: i64, yanked: bool, reason: &str) -> error::Result<()> {
+        let _lock = Lock::acquire(Lock::path(&self.modules_dir), false)?;
+        let track_json = self.modules_dir.join(id).join(constant::TRACK_JSON);
+        let mut track = Track::from_file(&track_json)?;
+
+        let version = track
+            .versions
+            .iter_mut()
+            .find(|v| v.version_code == version_code)
+            .ok_or_else(|| Error::custom(format!("Version `{version_code}` not found")))?;
+
+        version.yanked = yanked;
+        version.yanked_reason = reason.to_owned();
+        track.to_file(&track_json, true)
+    }
+
+    pub fn yank(&self, id: &str, version_code: i64, reason: &str) -> bool {
+        match self.modify(id, version_code, true, reason) {
+            Ok(_) => {
+                tracing::info!(target: "Yank::yank", %id, %version_code, %reason);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Yank::yank", %id, %version_code, ?error);
+                false
+            }
+        }
+    }
+
+    pub fn unyank(&self, id: &str, version_code: i64) -> bool {
+        match self.modify(id, version_code, false, "") {
+            Ok(_) => {
+                tracing::info!(target: "Yank::unyank", %id, %version_code);
+                true
+            }
+            Err(error) => {
+                tracing::error!(target: "Yank::unyank", %id, %version_code, ?error);
+                false
+            }
+        }
+    }
+}
+
+pub struct YankWrapper {
+    original: Arc<Yank>,
+}
+
+impl YankWrapper {
+    pub fn build(context: &Context) -> Self {
+        let yank = Yank::new(&context.modules_dir);
+
+        Self {
+            original: Arc::new(yank),
+        }
+    }
+}
+
+impl Deref for YankWrapper {
+    type Target = Yank;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
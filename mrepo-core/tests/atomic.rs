@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use mrepo_core::model::config::Config;
+use mrepo_core::model::track::Track;
+use mrepo_core::util::{ConfigFormat, Json};
+use mrepo_core::{constant, Init};
+
+use common::Repo;
+
+fn files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
+
+#[tokio::test]
+async fn track_recover() {
+    let repo = Repo::new("atomic-track");
+    let module = repo.module("{}");
+    let track_json = repo.module_dir().join(constant::TRACK_JSON);
+    let track_bak = repo.module_dir().join("track.json.bak");
+
+    repo.publish(1, "");
+    assert!(repo.update(&module).await);
+    assert!(!track_bak.exists());
+
+    repo.publish(2, "");
+    assert!(repo.update(&module).await);
+    assert_eq!(
+        files(&repo.module_dir()),
+        ["1.zip", "2.zip", "track.json", "track.json.bak"]
+    );
+
+    let previous = Track::from_file(&track_bak).unwrap();
+    assert_eq!(previous.module.version_code, 1);
+
+    fs::write(&track_json, "{\"version\": 1, \"mod").unwrap();
+    let track = Track::from_file(&track_json).unwrap();
+    assert_eq!(track, previous);
+
+    // A broken track.json never replaces the last good backup.
+    track.to_file(&track_json, true).unwrap();
+    assert_eq!(Track::from_file(&track_bak).unwrap(), previous);
+
+    fs::write(&track_json, "").unwrap();
+    fs::remove_file(&track_bak).unwrap();
+    assert!(Track::from_file(&track_json).is_err());
+}
+
+#[test]
+fn config_backup() {
+    let dir = common::temp_dir("atomic-config");
+    assert!(Init::new(&dir).init("Test", "https://repo.test.app", false, false));
+    let json_dir = dir.join(constant::JSON_DIR);
+    let config_json = json_dir.join(constant::CONFIG_JSON);
+    let config_bak = json_dir.join("config.json.bak");
+
+    let mut config: Config = ConfigFormat::read(&config_json).unwrap();
+    let before = fs::read(&config_json).unwrap();
+    config.repository.name = "Renamed".to_owned();
+    ConfigFormat::write(&config, &config_json).unwrap();
+
+    assert_eq!(fs::read(&config_bak).unwrap(), before);
+    let config: Config = ConfigFormat::read(&config_json).unwrap();
+    assert_eq!(config.repository.name, "Renamed");
+    assert!(!files(&json_dir).iter().any(|f| f.ends_with(".tmp")));
+
+    fs::write(&config_json, "{").unwrap();
+    assert!(ConfigFormat::read::<Config, _>(&config_json).is_err());
+    ConfigFormat::write(&config, &config_json).unwrap();
+    assert_eq!(fs::read(&config_bak).unwrap(), before);
+}
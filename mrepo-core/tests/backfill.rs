@@ -0,0 +1,76 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::model::config::RepositorySetting;
+use mrepo_core::Backfill;
+
+use common::{codes, Repo};
+
+fn setup(name: &str) -> Repo {
+    let repo = Repo::new(name);
+    for version_code in 1..=4 {
+        common::write_zip(
+            repo.dir.join(format!("zips/{version_code}.zip")),
+            "test",
+            version_code,
+            "",
+        );
+    }
+    common::write_zip(repo.dir.join("zips/other.zip"), "other", 5, "");
+
+    repo
+}
+
+async fn backfill(repo: &Repo, keep_size: usize) -> bool {
+    let module = repo.module(&format!(r#"{{"keep_size": {keep_size}}}"#));
+    let from = repo.dir.join("zips");
+
+    let backfill = Backfill::new(&RepositorySetting::default(), repo.modules_dir());
+    backfill.backfill(&module, from.to_str()).await
+}
+
+#[tokio::test]
+async fn limit() {
+    let repo = setup("backfill-limit");
+
+    assert!(backfill(&repo, 1).await);
+    assert_eq!(codes(&repo.track()), vec![4]);
+
+    assert!(backfill(&repo, 3).await);
+    assert_eq!(codes(&repo.track()), vec![4, 3, 2]);
+    for version_code in [2, 3, 4] {
+        assert!(repo
+            .module_dir()
+            .join(format!("{version_code}.zip"))
+            .exists());
+    }
+
+    assert!(!backfill(&repo, 3).await);
+    assert_eq!(codes(&repo.track()), vec![4, 3, 2]);
+}
+
+#[tokio::test]
+async fn cleanup() {
+    let repo = setup("backfill-cleanup");
+
+    assert!(backfill(&repo, 2).await);
+    let mut files: Vec<String> = fs::read_dir(repo.module_dir())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["3.zip", "4.zip", "track.json"]);
+}
+
+#[tokio::test]
+async fn order() {
+    let repo = setup("backfill-order");
+    repo.publish(2, "");
+    assert!(repo.update(&repo.module("{}")).await);
+
+    assert!(backfill(&repo, 4).await);
+    let track = repo.track();
+    assert_eq!(codes(&track), vec![4, 3, 2, 1]);
+    assert_eq!(track.module.version_code, 4);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use mrepo_core::model::config::Module;
+use mrepo_core::model::track::Track;
+use mrepo_core::util::Json;
+use mrepo_core::{Check, IssueKind};
+
+use common::Repo;
+
+async fn setup(name: &str) -> (PathBuf, Module) {
+    let repo = Repo::new(name);
+    repo.publish(1, "support=https://test.app\n");
+
+    let module = repo.module("{}");
+    assert!(repo.update(&module).await);
+
+    (repo.modules_dir(), module)
+}
+
+fn set_prop(modules_dir: &Path, prop: BTreeMap<String, String>) {
+    let track_json = modules_dir.join("test/track.json");
+    let mut track = Track::from_file(&track_json).unwrap();
+    track.module.prop = prop;
+    track.to_file(&track_json, true).unwrap();
+}
+
+fn prop_issues(modules_dir: &Path, module: &Module, fix: bool) -> usize {
+    Check::new(modules_dir)
+        .check(module, fix)
+        .iter()
+        .filter(|i| i.kind == IssueKind::PropMismatch(1, "prop"))
+        .count()
+}
+
+#[tokio::test]
+async fn empty_prop() {
+    let (modules_dir, module) = setup("check-empty-prop").await;
+    set_prop(&modules_dir, BTreeMap::new());
+    assert_eq!(prop_issues(&modules_dir, &module, false), 0);
+
+    prop_issues(&modules_dir, &module, true);
+    let track = Track::from_file(modules_dir.join("test/track.json")).unwrap();
+    assert_eq!(track.module.get_prop("support"), Some("https://test.app"));
+}
+
+#[tokio::test]
+async fn prop_mismatch() {
+    let (modules_dir, module) = setup("check-prop-mismatch").await;
+    set_prop(
+        &modules_dir,
+        BTreeMap::from([("support".to_owned(), "https://other.app".to_owned())]),
+    );
+    assert_eq!(prop_issues(&modules_dir, &module, false), 1);
+}
@@ -0,0 +1,263 @@
+#![allow(dead_code)]
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, process, thread};
+
+use serde_json::json;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use mrepo_core::model::config::{Module, RepositorySetting};
+use mrepo_core::model::track::Track;
+use mrepo_core::util::Json;
+use mrepo_core::Update;
+
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::SeqCst);
+    let dir = env::temp_dir().join(format!("mrepo-test-{}-{name}-{count}", process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+pub fn serve(root: &Path) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let root = root.to_owned();
+    let log = root.with_extension("log");
+
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request = String::new();
+            reader.read_line(&mut request).ok();
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap_or(0) > 2 {
+                header.clear();
+            }
+
+            let mut parts = request.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_owned();
+            let path = parts.next().unwrap_or_default().trim_start_matches('/');
+            if let Ok(mut log) = OpenOptions::new().create(true).append(true).open(&log) {
+                writeln!(log, "{method} /{path}").ok();
+            }
+
+            match fs::read(root.join(path)) {
+                Ok(body) => {
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .ok();
+                    if method != "HEAD" {
+                        stream.write_all(&body).ok();
+                    }
+                }
+                Err(_) => {
+                    write!(
+                        stream,
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                    .ok();
+                }
+            }
+        }
+    });
+
+    format!("http://{address}")
+}
+
+/// Requests seen by [`serve`] for `root`, as `"{method} /{path}"`.
+pub fn requests(root: &Path) -> Vec<String> {
+    fs::read_to_string(root.with_extension("log"))
+        .map(|s| s.lines().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+pub fn write_zip<P: AsRef<Path>>(path: P, id: &str, version_code: i64, extra: &str) {
+    write_zip_version(path, id, &format!("v{version_code}"), version_code, extra);
+}
+
+pub fn write_zip_version<P: AsRef<Path>>(
+    path: P,
+    id: &str,
+    version: &str,
+    version_code: i64,
+    extra: &str,
+) {
+    write_zip_inner(path.as_ref(), id, version, version_code, extra, &[]);
+}
+
+pub fn write_zip_files<P: AsRef<Path>>(
+    path: P,
+    id: &str,
+    version_code: i64,
+    extra: &str,
+    files: &[(&str, &str)],
+) {
+    let version = format!("v{version_code}");
+    write_zip_inner(path.as_ref(), id, &version, version_code, extra, files);
+}
+
+fn write_zip_inner(
+    path: &Path,
+    id: &str,
+    version: &str,
+    version_code: i64,
+    extra: &str,
+    files: &[(&str, &str)],
+) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    zip.start_file("module.prop", SimpleFileOptions::default())
+        .unwrap();
+    write!(
+        zip,
+        "id={id}\nname=Test {version_code}\nversion={version}\nversionCode={version_code}\nauthor=Test\ndescription=Test module\n{extra}"
+    )
+    .unwrap();
+    for (name, content) in files {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+/// A working directory with a served `srv/` upstream for a module `test`.
+pub struct Repo {
+    pub dir: PathBuf,
+    pub url: String,
+}
+
+impl Repo {
+    pub fn new(name: &str) -> Self {
+        let dir = temp_dir(name);
+        let url = serve(&dir.join("srv"));
+        Self { dir, url }
+    }
+
+    pub fn srv(&self) -> PathBuf {
+        self.dir.join("srv")
+    }
+
+    pub fn modules_dir(&self) -> PathBuf {
+        self.dir.join("modules")
+    }
+
+    pub fn module_dir(&self) -> PathBuf {
+        self.modules_dir().join("test")
+    }
+
+    /// A `zip-url` module following `srv/test.zip`.
+    pub fn module(&self, setting: &str) -> Module {
+        self.module_as("zip-url", "test.zip", setting)
+    }
+
+    pub fn module_as(&self, kind: &str, file: &str, setting: &str) -> Module {
+        let json = format!(
+            r#"{{"id": "test", "kind": "{kind}", "provider": "{}/{file}", "setting": {setting}}}"#,
+            self.url
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    pub fn publish(&self, version_code: i64, extra: &str) {
+        write_zip(self.srv().join("test.zip"), "test", version_code, extra);
+    }
+
+    pub fn publish_version(&self, version: &str, version_code: i64) {
+        write_zip_version(
+            self.srv().join("test.zip"),
+            "test",
+            version,
+            version_code,
+            "",
+        );
+    }
+
+    /// Writes `srv/modules.json` listing `(version_code, timestamp)` in the given order,
+    /// returns its url.
+    pub fn publish_index(&self, versions: &[(i64, i64)], extra: &str) -> String {
+        let srv = self.srv();
+        let versions: Vec<_> = versions
+            .iter()
+            .map(|(version_code, timestamp)| {
+                let name = format!("{version_code}-{timestamp}");
+                write_zip(
+                    srv.join(format!("{name}.zip")),
+                    "test",
+                    *version_code,
+                    extra,
+                );
+                fs::write(srv.join(format!("{name}.md")), "Changes").unwrap();
+                json!({
+                    "timestamp": timestamp,
+                    "version": format!("v{version_code}"),
+                    "version_code": version_code,
+                    "zip_url": format!("{}/{name}.zip", self.url),
+                    "changelog": format!("{}/{name}.md", self.url),
+                })
+            })
+            .collect();
+
+        let modules = json!({
+            "name": "Upstream",
+            "modules": [{
+                "id": "test",
+                "name": "Test",
+                "version": "",
+                "version_code": 0,
+                "author": "",
+                "description": "",
+                "versions": versions,
+            }]
+        });
+        fs::write(
+            srv.join("modules.json"),
+            serde_json::to_string(&modules).unwrap(),
+        )
+        .unwrap();
+
+        format!("{}/modules.json", self.url)
+    }
+
+    /// Writes `json/config.json` with the given module entries.
+    pub fn write_config(&self, modules: serde_json::Value) {
+        let config = json!({
+            "version": 1,
+            "log": { "disabled": true, "level": "info", "timestamp": false },
+            "repository": { "name": "Test", "setting": {} },
+            "modules": modules,
+        });
+        let json_dir = self.dir.join(mrepo_core::constant::JSON_DIR);
+        fs::create_dir_all(&json_dir).unwrap();
+        fs::write(
+            json_dir.join(mrepo_core::constant::CONFIG_JSON),
+            serde_json::to_string_pretty(&config).unwrap(),
+        )
+        .unwrap();
+    }
+
+    pub fn track(&self) -> Track {
+        Track::from_file(self.module_dir().join(mrepo_core::constant::TRACK_JSON)).unwrap()
+    }
+
+    pub async fn update(&self, module: &Module) -> bool {
+        let update = Update::new(&RepositorySetting::default(), self.modules_dir());
+        update.update(module).await
+    }
+}
+
+pub fn codes(track: &Track) -> Vec<i64> {
+    track.versions.iter().map(|v| v.version_code).collect()
+}
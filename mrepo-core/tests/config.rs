@@ -0,0 +1,62 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::model::config::Config;
+use mrepo_core::util::ConfigFormat;
+use mrepo_core::{constant, Edit, Init};
+
+fn example(name: &str) -> std::path::PathBuf {
+    let dir = common::temp_dir(name);
+    assert!(Init::new(&dir).init("Test", "https://repo.test.app", false, false));
+
+    let json_dir = dir.join(constant::JSON_DIR);
+    fs::remove_file(json_dir.join(constant::CONFIG_JSON)).unwrap();
+    dir
+}
+
+#[test]
+fn toml_keeps_comments() {
+    let dir = example("config-toml");
+    let json_dir = dir.join(constant::JSON_DIR);
+    let config_toml = json_dir.join("config.toml");
+    fs::rename(json_dir.join(constant::CONFIG_EXAMPLE), &config_toml).unwrap();
+    let before = fs::read_to_string(&config_toml).unwrap();
+
+    let edit = Edit::new(config_toml.to_owned(), dir.join("modules"));
+    assert!(edit.set_disabled("example", true));
+
+    let after = fs::read_to_string(&config_toml).unwrap();
+    let comments = |t: &str| -> Vec<String> {
+        t.lines()
+            .filter(|l| l.trim_start().starts_with('#'))
+            .map(str::to_owned)
+            .collect()
+    };
+    assert_eq!(comments(&before), comments(&after));
+
+    let config: Config = ConfigFormat::read(&config_toml).unwrap();
+    assert_eq!(config.modules[0].setting.disabled, Some(true));
+    assert_eq!(config.repository.setting.keep_size, 3);
+
+    assert!(edit.set_disabled("example", false));
+    let config: Config = ConfigFormat::read(&config_toml).unwrap();
+    assert_eq!(config.modules[0].setting.disabled, Some(false));
+}
+
+#[test]
+fn yaml_with_comments() {
+    let dir = example("config-yaml");
+    let json_dir = dir.join(constant::JSON_DIR);
+    let config: Config = ConfigFormat::read(json_dir.join(constant::CONFIG_EXAMPLE)).unwrap();
+    let config_yaml = json_dir.join("config.yaml");
+    ConfigFormat::write(&config, &config_yaml).unwrap();
+
+    let edit = Edit::new(config_yaml.to_owned(), dir.join("modules"));
+    assert!(edit.set_disabled("example", true));
+
+    let text = format!("# Comment\n{}", fs::read_to_string(&config_yaml).unwrap());
+    fs::write(&config_yaml, &text).unwrap();
+    assert!(!edit.set_disabled("example", false));
+    assert_eq!(fs::read_to_string(&config_yaml).unwrap(), text);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use serde_json::json;
+
+use mrepo_core::model::config::{Config, ModuleEntry};
+use mrepo_core::util::ConfigFormat;
+use mrepo_core::{ContextWrapper, Rollback};
+
+use common::Repo;
+
+async fn setup(name: &str) -> Repo {
+    let repo = Repo::new(name);
+    repo.write_config(json!([
+        { "id": "test", "kind": "zip-url", "provider": format!("{}/test.zip", repo.url) }
+    ]));
+
+    let module = repo.module("{}");
+    for version_code in 1..=3 {
+        repo.publish(version_code, "");
+        assert!(repo.update(&module).await);
+    }
+
+    repo
+}
+
+fn entry(repo: &Repo, id: &str) -> Option<ModuleEntry> {
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    let config: Config = ConfigFormat::read(&context.config_path).unwrap();
+    config.modules.into_iter().find(|m| m.id == id)
+}
+
+fn pin(repo: &Repo) -> Option<i64> {
+    entry(repo, "test").unwrap().setting.pin_version_code
+}
+
+#[tokio::test]
+async fn hold() {
+    let repo = setup("edit-hold").await;
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    let edit = context.edit();
+
+    assert!(edit.hold("test", None));
+    assert_eq!(pin(&repo), Some(3));
+
+    assert!(edit.hold("test", Some(1)));
+    assert_eq!(pin(&repo), Some(1));
+
+    assert!(edit.unhold("test"));
+    assert_eq!(pin(&repo), None);
+
+    assert!(Rollback::new(repo.modules_dir()).rollback("test", None));
+    assert!(edit.hold("test", None));
+    assert_eq!(pin(&repo), Some(2));
+
+    assert!(!edit.hold("missing", None));
+    assert!(!edit.unhold("missing"));
+}
+
+fn new_entry(repo: &Repo, id: &str) -> ModuleEntry {
+    serde_json::from_value(json!({
+        "id": id,
+        "kind": "zip-url",
+        "provider": format!("{}/{id}.zip", repo.url),
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn add_remove() {
+    let repo = setup("edit-add").await;
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    let edit = context.edit();
+
+    assert!(edit.add(new_entry(&repo, "other"), false).await);
+    let other = entry(&repo, "other").unwrap();
+    assert_eq!(other.provider, format!("{}/other.zip", repo.url));
+    assert!(other.metadata.support.is_empty());
+    assert!(!edit.add(new_entry(&repo, "other"), false).await);
+
+    common::write_zip(
+        repo.srv().join("probed.zip"),
+        "probed",
+        1,
+        "support=https://test.app\n",
+    );
+    assert!(edit.add(new_entry(&repo, "probed"), true).await);
+    let probed = entry(&repo, "probed").unwrap();
+    assert_eq!(probed.metadata.support, "https://test.app");
+    assert!(!repo.modules_dir().join("probed").exists());
+
+    assert!(!edit.add(new_entry(&repo, "missing"), true).await);
+    assert!(entry(&repo, "missing").is_none());
+
+    assert!(edit.remove("other"));
+    assert!(entry(&repo, "other").is_none());
+    assert!(entry(&repo, "test").is_some());
+    assert!(!edit.remove("other"));
+}
+
+#[tokio::test]
+async fn enable_disable() {
+    let repo = setup("edit-disable").await;
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    let edit = context.edit();
+    let disabled = || entry(&repo, "test").unwrap().setting.disabled;
+
+    assert!(edit.set_disabled("test", true));
+    assert_eq!(disabled(), Some(true));
+
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    assert!(context.modules[0].setting.is_disabled());
+
+    assert!(edit.set_disabled("test", false));
+    assert_eq!(disabled(), Some(false));
+    assert!(!edit.set_disabled("missing", true));
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use std::fs;
+
+use serde_json::Value;
+
+use mrepo_core::{constant, ContextWrapper, Init};
+
+#[test]
+fn write_to_same_file() {
+    let dir = common::temp_dir("format-same");
+    assert!(Init::new(&dir).init("Test", "https://repo.test.app", false, false));
+
+    let json_dir = dir.join(constant::JSON_DIR);
+    let config_json = json_dir.join(constant::CONFIG_JSON);
+    let mut config: Value = serde_json::from_slice(&fs::read(&config_json).unwrap()).unwrap();
+    config["include"] = serde_json::json!(["modules.d"]);
+    let module = config["modules"].as_array_mut().unwrap().remove(0);
+    fs::write(&config_json, serde_json::to_string(&config).unwrap()).unwrap();
+    fs::create_dir(json_dir.join("modules.d")).unwrap();
+    let module_json = json_dir.join("modules.d").join("example.json");
+    fs::write(&module_json, serde_json::to_string(&module).unwrap()).unwrap();
+
+    let context = ContextWrapper::from_working_dir(&dir).unwrap();
+    let spelling = json_dir
+        .join("..")
+        .join(constant::JSON_DIR)
+        .join(constant::CONFIG_JSON);
+    assert!(context.format().write_to(&spelling));
+
+    let config: Value = serde_json::from_slice(&fs::read(&config_json).unwrap()).unwrap();
+    assert_eq!(config["include"], serde_json::json!(["modules.d"]));
+    assert!(config["modules"].as_array().unwrap().is_empty());
+    assert!(module_json.exists());
+}
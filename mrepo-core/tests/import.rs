@@ -0,0 +1,83 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::model::config::{Config, ProviderKind};
+use mrepo_core::model::track::Track;
+use mrepo_core::util::{ConfigFormat, Json};
+use mrepo_core::{constant, ContextWrapper, Init};
+
+use common::{codes, Repo};
+
+#[tokio::test]
+async fn with_files() {
+    let repo = Repo::new("import-files");
+    let source = repo.publish_index(&[(2, 20), (3, 30), (1, 10)], "");
+    assert!(Init::new(&repo.dir).init("Test", "https://repo.test.app", false, false));
+
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    assert!(context.import().import(&source, true).await);
+
+    let config: Config = ConfigFormat::read(&context.config_path).unwrap();
+    let entry = config.modules.iter().find(|m| m.id == "test").unwrap();
+    assert_eq!(entry.kind, Some(ProviderKind::Mirror));
+    assert_eq!(entry.provider, source);
+
+    let module_dir = context.modules_dir.join("test");
+    let track = Track::from_file(module_dir.join(constant::TRACK_JSON)).unwrap();
+    assert_eq!(codes(&track), vec![2, 3, 1]);
+    assert_eq!(track.module.version_code, 2);
+    assert_eq!(track.module.name, "Test 2");
+
+    let mut files: Vec<String> = fs::read_dir(&module_dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "1.md",
+            "1.zip",
+            "2.md",
+            "2.zip",
+            "3.md",
+            "3.zip",
+            constant::TRACK_JSON
+        ]
+    );
+}
+
+#[tokio::test]
+async fn without_files() {
+    let repo = Repo::new("import-entries");
+    let source = repo.publish_index(&[(1, 10)], "");
+    assert!(Init::new(&repo.dir).init("Test", "https://repo.test.app", false, false));
+
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    assert!(context.import().import(&source, false).await);
+    assert!(!context.modules_dir.join("test").exists());
+
+    assert!(context.import().import(&source, false).await);
+    let config: Config = ConfigFormat::read(&context.config_path).unwrap();
+    assert_eq!(config.modules.iter().filter(|m| m.id == "test").count(), 1);
+}
+
+#[tokio::test]
+async fn retention() {
+    let repo = Repo::new("import-retention");
+    let versions: Vec<(i64, i64)> = (1..=5).rev().map(|c| (c, c * 10)).collect();
+    let source = repo.publish_index(&versions, "");
+    assert!(Init::new(&repo.dir).init("Test", "https://repo.test.app", false, false));
+
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    assert!(context.import().import(&source, true).await);
+
+    let track =
+        Track::from_file(context.modules_dir.join("test").join(constant::TRACK_JSON)).unwrap();
+    assert_eq!(codes(&track), vec![5, 4, 3]);
+
+    let requests = common::requests(&repo.srv());
+    let zips = requests.iter().filter(|r| r.ends_with(".zip")).count();
+    assert_eq!(zips, 3);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::fs;
+
+use serde_json::json;
+
+use mrepo_core::model::config::RootSupport;
+use mrepo_core::{ContextWrapper, Rollback};
+
+use common::Repo;
+
+async fn setup(name: &str) -> Repo {
+    let repo = Repo::new(name);
+    repo.write_config(json!([
+        { "id": "test", "kind": "zip-url", "provider": format!("{}/test.zip", repo.url) },
+        { "id": "other", "kind": "zip-url", "provider": format!("{}/other.zip", repo.url) }
+    ]));
+
+    let module = repo.module("{}");
+    for version_code in 1..=2 {
+        repo.publish(version_code, "");
+        assert!(repo.update(&module).await);
+    }
+
+    repo
+}
+
+#[tokio::test]
+async fn list() {
+    let repo = setup("info-list").await;
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+
+    let infos = context.info().list();
+    let ids: Vec<&str> = infos.iter().map(|i| i.config.id.as_str()).collect();
+    assert_eq!(ids, ["test", "other"]);
+
+    let other = &infos[1];
+    assert!(other.module.is_none());
+    assert!(other.root.is_none());
+    assert!(other.versions.is_empty());
+    assert!(other.latest().is_none());
+    assert_eq!(other.size(), 0);
+
+    let value = serde_json::to_value(other).unwrap();
+    assert_eq!(value["id"], "other");
+    assert_eq!(value["kind"], "zip-url");
+    assert!(value.get("module").is_none());
+    assert!(value.get("root").is_none());
+}
+
+#[tokio::test]
+async fn info() {
+    let repo = setup("info-find").await;
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    assert!(context.info().find("missing").is_none());
+
+    let info = context.info().find("test").unwrap();
+    assert_eq!(info.module.as_ref().unwrap().version_code, 2);
+    assert_eq!(info.root, Some(RootSupport::default()));
+
+    let zip_size = fs::metadata(repo.module_dir().join("2.zip")).unwrap().len();
+    let latest = info.latest().unwrap();
+    assert_eq!(latest.version.version_code, 2);
+    assert_eq!(latest.zip_size, Some(zip_size));
+    assert_eq!(latest.changelog_size, None);
+
+    let sizes: u64 = info.versions.iter().filter_map(|v| v.zip_size).sum();
+    assert_eq!(info.size(), sizes);
+
+    let value = serde_json::to_value(&info).unwrap();
+    assert_eq!(value["id"], "test");
+    assert_eq!(value["versions"][0]["version_code"], 2);
+    assert_eq!(value["versions"][0]["zip_size"], zip_size);
+
+    assert!(Rollback::new(repo.modules_dir()).rollback("test", None));
+    let info = context.info().find("test").unwrap();
+    assert_eq!(info.versions.len(), 2);
+    assert_eq!(info.latest().unwrap().version.version_code, 1);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::model::config::Config;
+use mrepo_core::util::ConfigFormat;
+use mrepo_core::{constant, Init, Validate};
+
+#[test]
+fn example() {
+    let dir = common::temp_dir("init-example");
+    assert!(Init::new(&dir).init("Test \"Repo\"", "https://repo.test.app", false, false));
+
+    let json_dir = dir.join(constant::JSON_DIR);
+    let example = json_dir.join(constant::CONFIG_EXAMPLE);
+    let text = fs::read_to_string(&example).unwrap();
+    assert!(text.starts_with("# "));
+
+    let config: Config = ConfigFormat::read(&example).unwrap();
+    assert_eq!(config.repository.name, "Test \"Repo\"");
+    assert_eq!(config.repository.setting.base_url, "https://repo.test.app");
+    assert_eq!(config.modules[0].id, "example");
+
+    let json: Config = ConfigFormat::read(json_dir.join(constant::CONFIG_JSON)).unwrap();
+    assert_eq!(config, json);
+
+    assert!(Validate::new(&example).validate().unwrap().is_empty());
+    assert!(Validate::new(json_dir.join(constant::CONFIG_JSON))
+        .validate()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn existing() {
+    for name in ["config.json", "config.toml", "config.yaml", "config.yml"] {
+        let dir = common::temp_dir("init-existing");
+        let json_dir = dir.join(constant::JSON_DIR);
+        fs::create_dir_all(&json_dir).unwrap();
+        fs::write(json_dir.join(name), "").unwrap();
+
+        assert!(!Init::new(&dir).init("Test", "", false, false), "{name}");
+        assert!(!json_dir.join(constant::CONFIG_EXAMPLE).exists(), "{name}");
+    }
+}
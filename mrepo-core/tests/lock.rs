@@ -0,0 +1,45 @@
+mod common;
+
+use std::fs::File;
+
+use mrepo_core::util::Lock;
+use mrepo_core::{constant, ContextWrapper, Init};
+
+#[test]
+fn mutating_operations() {
+    let dir = common::temp_dir("lock");
+    assert!(Init::new(&dir).init("Test", "https://repo.test.app", false, false));
+    let context = ContextWrapper::from_working_dir(&dir).unwrap();
+    let lock_file = dir.join(constant::JSON_DIR).join(constant::LOCK_FILE);
+    assert_eq!(Lock::path(&context.modules_dir), lock_file);
+
+    let other = File::create(&lock_file).unwrap();
+    other.try_lock().unwrap();
+    assert!(!context.edit().set_disabled("example", true));
+    assert!(context.lock(false).is_err());
+
+    other.unlock().unwrap();
+    let lock = context.lock(false).unwrap();
+    assert!(context.edit().set_disabled("example", true));
+    assert!(other.try_lock().is_err());
+
+    drop(lock);
+    assert!(!lock_file.exists());
+    other.try_lock().unwrap();
+
+    // `other` now holds a removed file, which doesn't block a new lock.
+    let lock = context.lock(false).unwrap();
+    assert!(lock_file.exists());
+    drop(lock);
+}
+
+#[test]
+fn nested_entry_points() {
+    let dir = common::temp_dir("lock-nested");
+    assert!(Init::new(&dir).init("Test", "https://repo.test.app", false, false));
+    let context = ContextWrapper::from_working_dir(&dir).unwrap();
+
+    assert!(context.format().migrate());
+    context.check().check_all(true);
+    assert!(!Lock::path(&context.modules_dir).exists());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use mrepo_core::model::config::Module;
+
+use common::{codes, Repo};
+
+fn module(repo: &Repo, setting: &str) -> Module {
+    repo.module_as("mirror", "modules.json", setting)
+}
+
+#[tokio::test]
+async fn publish_order() {
+    let repo = Repo::new("mirror-order");
+    let module = module(&repo, r#"{"keep_size": 3}"#);
+
+    repo.publish_index(&[(2, 30), (3, 20), (1, 10)], "");
+    assert!(repo.update(&module).await);
+
+    let track = repo.track();
+    assert_eq!(codes(&track), vec![2, 3, 1]);
+    assert_eq!(track.module.version_code, 2);
+    assert!(!repo.update(&module).await);
+}
+
+#[tokio::test]
+async fn downgrade() {
+    for (policy, accepted) in [("ignore", false), ("warn", false), ("accept", true)] {
+        let repo = Repo::new(&format!("mirror-downgrade-{policy}"));
+        let module = module(
+            &repo,
+            &format!(r#"{{"keep_size": 3, "downgrade": "{policy}"}}"#),
+        );
+
+        repo.publish_index(&[(3, 20), (1, 10)], "");
+        assert!(repo.update(&module).await);
+
+        repo.publish_index(&[(2, 30), (3, 20), (1, 10)], "");
+        assert_eq!(repo.update(&module).await, accepted, "{policy}");
+
+        let track = repo.track();
+        if accepted {
+            assert_eq!(codes(&track), vec![2, 3, 1]);
+            assert_eq!(track.module.version_code, 2);
+        } else {
+            assert_eq!(codes(&track), vec![3, 1], "{policy}");
+            assert_eq!(track.module.version_code, 3, "{policy}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn reupload() {
+    for (policy, accepted) in [("ignore", false), ("warn", false), ("accept", true)] {
+        let repo = Repo::new(&format!("mirror-reupload-{policy}"));
+        let module = module(&repo, &format!(r#"{{"reupload": "{policy}"}}"#));
+
+        repo.publish_index(&[(1, 10)], "");
+        assert!(repo.update(&module).await);
+        let checksum = repo.track().versions[0].checksum.to_owned();
+
+        repo.publish_index(&[(1, 20)], "updateJson=changed\n");
+        assert_eq!(repo.update(&module).await, accepted, "{policy}");
+
+        let track = repo.track();
+        let version = &track.versions[0];
+        assert_eq!(codes(&track), vec![1], "{policy}");
+        assert_eq!(version.checksum != checksum, accepted, "{policy}");
+        assert_eq!(version.timestamp == 20, accepted, "{policy}");
+        assert!(repo.module_dir().join(&version.zip_file).exists());
+    }
+}
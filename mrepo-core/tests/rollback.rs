@@ -0,0 +1,63 @@
+mod common;
+
+use mrepo_core::model::config::Module;
+use mrepo_core::model::track::Track;
+use mrepo_core::Rollback;
+
+use common::Repo;
+
+async fn publish(repo: &Repo, module: &Module, version_code: i64) {
+    repo.publish(version_code, "");
+    assert!(repo.update(module).await);
+}
+
+fn rollback(repo: &Repo, to: Option<i64>) -> bool {
+    Rollback::new(repo.modules_dir()).rollback("test", to)
+}
+
+fn published(track: &Track) -> Vec<(i64, bool)> {
+    track
+        .versions
+        .iter()
+        .map(|v| (v.version_code, v.withdrawn))
+        .collect()
+}
+
+#[tokio::test]
+async fn previous() {
+    let repo = Repo::new("rollback-previous");
+    let module = repo.module("{}");
+    for version_code in 1..=3 {
+        publish(&repo, &module, version_code).await;
+    }
+
+    assert!(rollback(&repo, None));
+    let track = repo.track();
+    assert_eq!(published(&track), vec![(3, true), (2, false), (1, false)]);
+    assert_eq!(track.module.version_code, 2);
+    assert_eq!(track.module.name, "Test 2");
+
+    assert!(rollback(&repo, Some(1)));
+    let track = repo.track();
+    assert_eq!(published(&track), vec![(3, true), (2, true), (1, false)]);
+    assert_eq!(track.module.version_code, 1);
+
+    assert!(!rollback(&repo, None));
+    assert!(rollback(&repo, Some(3)));
+    assert_eq!(repo.track().module.version_code, 3);
+}
+
+#[tokio::test]
+async fn keep_size() {
+    let repo = Repo::new("rollback-keep-size");
+    let module = repo.module(r#"{"keep_size": 2}"#);
+    publish(&repo, &module, 1).await;
+    publish(&repo, &module, 2).await;
+
+    assert!(rollback(&repo, None));
+    publish(&repo, &module, 3).await;
+
+    let track = repo.track();
+    assert_eq!(published(&track), vec![(3, false), (2, true), (1, false)]);
+    assert_eq!(track.module.version_code, 3);
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use mrepo_core::model::config::{Module, Repository, RepositorySetting, RootSupport};
+use mrepo_core::model::track::{Track, Version};
+use mrepo_core::util::{Json, LocalModule, Root};
+use mrepo_core::{constant, Upgrade};
+
+fn detect(name: &str, extra: &str, files: &[(&str, &str)]) -> RootSupport {
+    let path = common::temp_dir(name).join("test.zip");
+    common::write_zip_files(&path, "test", 1, extra, files);
+    Root::detect(&path).unwrap()
+}
+
+fn support(magisk: bool, kernelsu: bool, apatch: bool) -> RootSupport {
+    RootSupport {
+        magisk,
+        kernelsu,
+        apatch,
+    }
+}
+
+#[test]
+fn files() {
+    let all = support(true, true, true);
+    assert_eq!(detect("root-files", "", &[]), all);
+    assert_eq!(
+        detect("root-files", "", &[("webroot/index.html", "")]),
+        support(false, true, true)
+    );
+    assert_eq!(
+        detect("root-files", "", &[("post-mount.sh", "")]),
+        support(false, true, true)
+    );
+    assert_eq!(
+        detect(
+            "root-files",
+            "",
+            &[("webroot/index.html", ""), ("service.sh", "")]
+        ),
+        all
+    );
+}
+
+#[test]
+fn script() {
+    let script = |s: &str| detect("root-script", "", &[("customize.sh", s)]);
+
+    assert_eq!(
+        script("if [ \"$KSU\" = \"true\" ]; then\n  abort \"KernelSU is not supported\"\nfi\n"),
+        support(true, false, true)
+    );
+    assert_eq!(
+        script("[ \"$APATCH\" = true ] && abort \"APatch is not supported\"\n"),
+        support(true, true, false)
+    );
+    assert_eq!(
+        script("if [ -z \"$KSU\" ]; then\n  abort \"KernelSU only\"\nfi\n"),
+        support(false, true, false)
+    );
+    assert_eq!(
+        script("[ \"${APATCH}\" != true ] && abort \"APatch only\"\n"),
+        support(false, false, true)
+    );
+    assert_eq!(
+        script("# [ \"$KSU\" = true ] && abort\nui_print \"$KSU\"\n"),
+        support(true, true, true)
+    );
+}
+
+#[test]
+fn prop() {
+    assert_eq!(
+        detect("root-prop", "kernelsu=false\n", &[]),
+        support(true, false, true)
+    );
+    assert_eq!(
+        detect("root-prop", "magisk=true\n", &[("webroot/index.html", "")]),
+        support(true, true, true)
+    );
+    assert_eq!(
+        detect(
+            "root-prop",
+            "apatch=true\n",
+            &[("customize.sh", "[ \"$APATCH\" = true ] && abort\n")]
+        ),
+        support(true, true, true)
+    );
+    assert_eq!(
+        detect("root-prop", "kernelsu=maybe\n", &[]),
+        support(true, true, true)
+    );
+}
+
+#[test]
+fn config_override() {
+    let dir = common::temp_dir("root-override");
+    let modules_dir = dir.join("modules");
+    let module_dir = modules_dir.join("test");
+    common::write_zip_files(
+        module_dir.join("1.zip"),
+        "test",
+        1,
+        "kernelsu=false\n",
+        &[("webroot/index.html", "")],
+    );
+
+    let local = LocalModule::read_zip(module_dir.join("1.zip")).unwrap();
+    let track = Track::new(local, vec![Version::new(0, "v1".to_owned(), 1)]);
+    track
+        .to_file(module_dir.join(constant::TRACK_JSON), true)
+        .unwrap();
+
+    let root = |setting: &str| {
+        let json = format!(
+            r#"{{"id": "test", "kind": "zip-url", "provider": "https://repo.test.app/test.zip", "setting": {setting}}}"#
+        );
+        let module: Module = serde_json::from_str(&json).unwrap();
+        generate(&dir, &modules_dir, module)
+    };
+
+    assert_eq!(root("{}"), Some(support(false, false, true)));
+    assert_eq!(
+        root(r#"{"root": {"magisk": true, "kernelsu": true}}"#),
+        Some(support(true, true, true))
+    );
+    assert_eq!(
+        root(r#"{"root": {"apatch": false}}"#),
+        Some(support(false, false, false))
+    );
+}
+
+fn generate(dir: &Path, modules_dir: &Path, module: Module) -> Option<RootSupport> {
+    let repository = Repository::new("Test", None, RepositorySetting::default());
+    let upgrade = Upgrade::new(
+        &repository,
+        dir.join(constant::JSON_DIR),
+        modules_dir.to_owned(),
+    );
+    let modules = upgrade.generate_modules(&[Arc::new(module)]);
+    modules[0].root
+}
@@ -0,0 +1,36 @@
+use mrepo_core::util::JsonSpan;
+
+#[test]
+fn position() {
+    let span = JsonSpan::new(
+        r#"{
+  "repository": {
+    "name": "Test",
+    "setting": { "keep_size": 3 }
+  },
+  "modules": [
+    {
+      "id": "a/b",
+      "setting": {}
+    },
+    { "id": "test" }
+  ]
+}"#,
+    );
+
+    assert_eq!(span.position("/repository"), (2, 17));
+    assert_eq!(span.position("/repository/name"), (3, 13));
+    assert_eq!(span.position("/repository/setting/keep_size"), (4, 31));
+    assert_eq!(span.position("/modules/0/id"), (8, 13));
+    assert_eq!(span.position("/modules/1"), (11, 5));
+    assert_eq!(span.position("/modules/1/id"), (11, 13));
+}
+
+#[test]
+fn position_fallback() {
+    let span = JsonSpan::new(r#"{"modules": [{"id": "test"}]}"#);
+
+    assert_eq!(span.position("/modules/0/setting/keep_size"), (1, 14));
+    assert_eq!(span.position("/unknown"), (1, 1));
+    assert_eq!(span.position(""), (1, 1));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::model::config::Module;
+use mrepo_core::{Available, State, Status};
+
+use common::Repo;
+
+fn publish_json(repo: &Repo, version_code: i64) {
+    fs::create_dir_all(repo.srv()).unwrap();
+    fs::write(
+        repo.srv().join("update.json"),
+        format!(
+            r#"{{"version": "v{version_code}", "versionCode": {version_code}, "zipUrl": "{}/test.zip", "changelog": ""}}"#,
+            repo.url
+        ),
+    )
+    .unwrap();
+    repo.publish(version_code, "");
+}
+
+async fn status(repo: &Repo, module: &Module) -> (Available, State) {
+    let status = Status::new(repo.modules_dir()).status(module).await;
+    (status.available, status.state)
+}
+
+#[tokio::test]
+async fn update_json() {
+    let repo = Repo::new("status-json");
+    let module = repo.module_as("update-json", "update.json", "{}");
+    let v = |version_code: i64| {
+        Available::Version(format!("v{version_code} ({version_code})"), version_code)
+    };
+
+    publish_json(&repo, 1);
+    assert_eq!(status(&repo, &module).await, (v(1), State::Outdated));
+
+    assert!(repo.update(&module).await);
+    assert_eq!(status(&repo, &module).await, (v(1), State::Latest));
+
+    publish_json(&repo, 2);
+    assert_eq!(status(&repo, &module).await, (v(2), State::Outdated));
+
+    let pinned = repo.module_as("update-json", "update.json", r#"{"pin_version_code": 1}"#);
+    assert_eq!(status(&repo, &pinned).await, (v(2), State::Held));
+
+    let current = Status::new(repo.modules_dir())
+        .status(&pinned)
+        .await
+        .current
+        .unwrap();
+    assert_eq!(current.version_code, 1);
+}
+
+#[tokio::test]
+async fn mirror() {
+    let repo = Repo::new("status-mirror");
+    let module = repo.module_as("mirror", "modules.json", "{}");
+    let v = |version_code: i64| Available::Version(format!("v{version_code}"), version_code);
+
+    repo.publish_index(&[(1, 10)], "");
+    assert!(repo.update(&module).await);
+    assert_eq!(status(&repo, &module).await, (v(1), State::Latest));
+
+    repo.publish_index(&[(2, 20), (1, 10)], "");
+    assert_eq!(status(&repo, &module).await, (v(2), State::Outdated));
+}
+
+#[tokio::test]
+async fn unavailable() {
+    let repo = Repo::new("status-unavailable");
+
+    let module = repo.module_as("update-json", "missing.json", "{}");
+    assert_eq!(
+        status(&repo, &module).await,
+        (Available::Failed, State::Unknown)
+    );
+
+    let module = repo.module_as("update-json", "missing.json", r#"{"disabled": true}"#);
+    assert_eq!(
+        status(&repo, &module).await,
+        (Available::Unknown, State::Disabled)
+    );
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use mrepo_core::util::StrUtil;
+
+#[test]
+fn expand_env() {
+    env::set_var("MREPO_TEST_HOST", "repo.test.app");
+    env::set_var("MREPO_TEST_EMPTY", "");
+
+    let mut missing = Vec::new();
+    assert_eq!(
+        StrUtil::expand_env("https://${MREPO_TEST_HOST}/modules", &mut missing),
+        "https://repo.test.app/modules"
+    );
+    assert_eq!(
+        StrUtil::expand_env("${MREPO_TEST_UNSET:-fallback}", &mut missing),
+        "fallback"
+    );
+    assert_eq!(
+        StrUtil::expand_env("${MREPO_TEST_EMPTY:-fallback}", &mut missing),
+        "fallback"
+    );
+    assert_eq!(
+        StrUtil::expand_env("${MREPO_TEST_HOST:-fallback}", &mut missing),
+        "repo.test.app"
+    );
+    assert_eq!(
+        StrUtil::expand_env("$HOME {id}", &mut missing),
+        "$HOME {id}"
+    );
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn expand_env_escape() {
+    env::set_var("MREPO_TEST_ESCAPED", "expanded");
+
+    let mut missing = Vec::new();
+    assert_eq!(
+        StrUtil::expand_env("$${MREPO_TEST_ESCAPED}", &mut missing),
+        "${MREPO_TEST_ESCAPED}"
+    );
+    assert_eq!(
+        StrUtil::expand_env("$${MREPO_TEST_ESCAPED} ${MREPO_TEST_ESCAPED}", &mut missing),
+        "${MREPO_TEST_ESCAPED} expanded"
+    );
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn expand_env_missing() {
+    let mut missing = Vec::new();
+    assert_eq!(
+        StrUtil::expand_env(
+            "${MREPO_TEST_MISSING_A}/${MREPO_TEST_MISSING_B}",
+            &mut missing
+        ),
+        "/"
+    );
+    assert_eq!(
+        missing,
+        vec!["MREPO_TEST_MISSING_A", "MREPO_TEST_MISSING_B"]
+    );
+}
+
+#[test]
+fn expand_vars() {
+    let vars = BTreeMap::from([
+        ("id".to_owned(), "test".to_owned()),
+        ("github".to_owned(), "https://github.com/test".to_owned()),
+    ]);
+
+    let mut unknown = Vec::new();
+    assert_eq!(
+        StrUtil::expand_vars("{github}/{id}/releases", &vars, &mut unknown),
+        "https://github.com/test/test/releases"
+    );
+    assert_eq!(
+        StrUtil::expand_vars("${id} {id}", &vars, &mut unknown),
+        "${id} test"
+    );
+    assert!(unknown.is_empty());
+
+    assert_eq!(
+        StrUtil::expand_vars("{owner}/{id}", &vars, &mut unknown),
+        "{owner}/test"
+    );
+    assert_eq!(unknown, vec!["owner"]);
+}
+
+#[test]
+fn get_major_version() {
+    assert_eq!(StrUtil::get_major_version("v1.9.2 (7024)"), Some(1));
+    assert_eq!(StrUtil::get_major_version("2.0"), Some(2));
+    assert_eq!(StrUtil::get_major_version("V3-beta"), Some(3));
+    assert_eq!(StrUtil::get_major_version("Build 2024"), None);
+}
+
+#[test]
+fn matches_tag() {
+    assert!(StrUtil::matches_tag("v1.0 (10)", "v1.0"));
+    assert!(StrUtil::matches_tag("1.0 (10)", "v1.0"));
+    assert!(StrUtil::matches_tag("v1.0", "1.0"));
+    assert!(!StrUtil::matches_tag("v1.0.1 (11)", "v1.0"));
+    assert!(!StrUtil::matches_tag("v1.0 (10)", "v1.0.1"));
+    assert!(!StrUtil::matches_tag("v1.0 (10)", ""));
+}
+
+#[test]
+fn line_column() {
+    let text = "a\nbé\nc";
+    assert_eq!(StrUtil::line_column(text, 0), (1, 1));
+    assert_eq!(StrUtil::line_column(text, 3), (2, 2));
+    assert_eq!(StrUtil::line_column(text, 4), (2, 2));
+    assert_eq!(StrUtil::line_column(text, 5), (2, 3));
+    assert_eq!(StrUtil::line_column(text, 100), (3, 2));
+}
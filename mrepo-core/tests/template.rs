@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use mrepo_core::model::config::*;
+use mrepo_core::util::{ConfigFormat, Templates};
+
+fn entry(json: &str) -> ModuleEntry {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn escape() {
+    env::set_var("MREPO_TEST_TOKEN", "secret");
+
+    let defaults = ModuleTemplate::default();
+    let templates = BTreeMap::new();
+    let templates = Templates::new(&defaults, &templates);
+    let entry = entry(
+        r#"
+        {
+          "id": "test",
+          "kind": "zip-url",
+          "provider": "https://test.app/{id}.zip?token=${MREPO_TEST_TOKEN}&raw=$${MREPO_TEST_TOKEN}"
+        }"#,
+    );
+    let expected = "https://test.app/test.zip?token=secret&raw=${MREPO_TEST_TOKEN}";
+
+    let module = templates.resolve(&entry).unwrap();
+    let module = ConfigFormat::expand_env(&module).unwrap();
+    assert_eq!(module.provider, expected);
+
+    let entry = ConfigFormat::expand_env(&entry).unwrap();
+    let module = templates.resolve(&entry).unwrap();
+    assert_eq!(module.provider, expected);
+}
+
+#[test]
+fn missing_env() {
+    let entry = entry(
+        r#"
+        {
+          "id": "test",
+          "kind": "zip-url",
+          "provider": "https://test.app/${MREPO_TEST_NOT_SET}.zip"
+        }"#,
+    );
+
+    let error = ConfigFormat::expand_env(&entry).unwrap_err().to_string();
+    assert!(error.contains("`MREPO_TEST_NOT_SET` at /provider"));
+}
+
+#[test]
+fn setting_override() {
+    let defaults: ModuleTemplate = serde_json::from_str(
+        r#"
+        {
+          "kind": "zip-url",
+          "provider": "https://test.app/{id}.zip",
+          "setting": {
+            "disabled": true,
+            "downgrade": "warn",
+            "reupload": "warn"
+          }
+        }"#,
+    )
+    .unwrap();
+    let templates: BTreeMap<String, ModuleTemplate> = serde_json::from_str(
+        r#"
+        {
+          "lenient": {
+            "setting": {
+              "reupload": "accept"
+            }
+          }
+        }"#,
+    )
+    .unwrap();
+    let templates = Templates::new(&defaults, &templates);
+
+    let module = templates.resolve(&entry(r#"{"id": "test"}"#)).unwrap();
+    assert!(module.setting.is_disabled());
+    assert_eq!(module.setting.downgrade_policy(), VersionPolicy::Warn);
+    assert_eq!(module.setting.reupload_policy(), VersionPolicy::Warn);
+
+    let module = templates
+        .resolve(&entry(r#"{"id": "test", "template": "lenient"}"#))
+        .unwrap();
+    assert_eq!(module.setting.reupload_policy(), VersionPolicy::Accept);
+
+    let module = templates
+        .resolve(&entry(
+            r#"
+            {
+              "id": "test",
+              "template": "lenient",
+              "setting": {
+                "disabled": false,
+                "downgrade": "ignore",
+                "reupload": "ignore"
+              }
+            }"#,
+        ))
+        .unwrap();
+    assert!(!module.setting.is_disabled());
+    assert_eq!(module.setting.downgrade_policy(), VersionPolicy::Ignore);
+    assert_eq!(module.setting.reupload_policy(), VersionPolicy::Ignore);
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use std::fs;
+use std::sync::Arc;
+
+use serde_json::json;
+
+use mrepo_core::model::config::{Repository, RepositorySetting};
+use mrepo_core::model::modules;
+use mrepo_core::{constant, ContextWrapper, Upgrade};
+
+use common::{codes, Repo};
+
+fn codes_of(module: &modules::Module) -> Vec<i64> {
+    module.versions.iter().map(|v| v.version_code).collect()
+}
+
+fn exists(repo: &Repo, zip_file: &str) -> bool {
+    repo.module_dir().join(zip_file).exists()
+}
+
+#[tokio::test]
+async fn new_version() {
+    let repo = Repo::new("update-new");
+    let module = repo.module("{}");
+
+    repo.publish(1, "");
+    assert!(repo.update(&module).await);
+    repo.publish(2, "");
+    assert!(repo.update(&module).await);
+
+    let track = repo.track();
+    assert_eq!(codes(&track), vec![2, 1]);
+    assert_eq!(track.module.version_code, 2);
+    assert!(exists(&repo, "2.zip"));
+
+    assert!(!repo.update(&module).await);
+    assert_eq!(repo.track(), track);
+}
+
+#[tokio::test]
+async fn reupload() {
+    for (policy, accepted) in [("ignore", false), ("warn", false), ("accept", true)] {
+        let repo = Repo::new(&format!("update-reupload-{policy}"));
+        let module = repo.module(&format!(r#"{{"reupload": "{policy}"}}"#));
+
+        repo.publish(1, "");
+        assert!(repo.update(&module).await);
+        let checksum = repo.track().versions[0].checksum.to_owned();
+
+        repo.publish(1, "updateJson=changed\n");
+        assert_eq!(repo.update(&module).await, accepted, "{policy}");
+
+        let track = repo.track();
+        assert_eq!(codes(&track), vec![1], "{policy}");
+        assert_eq!(track.versions[0].checksum != checksum, accepted, "{policy}");
+        assert!(exists(&repo, &track.versions[0].zip_file), "{policy}");
+    }
+}
+
+#[tokio::test]
+async fn reupload_json() {
+    for (policy, downloads) in [("ignore", 1), ("warn", 2)] {
+        let repo = Repo::new(&format!("update-reupload-json-{policy}"));
+        let module = repo.module_as(
+            "update-json",
+            "update.json",
+            &format!(r#"{{"reupload": "{policy}"}}"#),
+        );
+        fs::create_dir_all(repo.srv()).unwrap();
+        fs::write(
+            repo.srv().join("update.json"),
+            format!(
+                r#"{{"version": "v1", "versionCode": 1, "zipUrl": "{}/test.zip", "changelog": ""}}"#,
+                repo.url
+            ),
+        )
+        .unwrap();
+
+        repo.publish(1, "");
+        assert!(repo.update(&module).await, "{policy}");
+        assert!(!repo.update(&module).await, "{policy}");
+
+        let requests = common::requests(&repo.srv());
+        let zips = requests.iter().filter(|r| r.ends_with("/test.zip")).count();
+        assert_eq!(zips, downloads, "{policy}");
+    }
+}
+
+#[tokio::test]
+async fn downgrade() {
+    for (policy, accepted) in [("ignore", false), ("warn", false), ("accept", true)] {
+        let repo = Repo::new(&format!("update-downgrade-{policy}"));
+        let module = repo.module(&format!(r#"{{"downgrade": "{policy}"}}"#));
+
+        repo.publish(2, "");
+        assert!(repo.update(&module).await);
+
+        repo.publish(1, "");
+        assert_eq!(repo.update(&module).await, accepted, "{policy}");
+
+        let track = repo.track();
+        if accepted {
+            assert_eq!(codes(&track), vec![1, 2]);
+            assert_eq!(track.module.version_code, 1);
+        } else {
+            assert_eq!(codes(&track), vec![2], "{policy}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn downgrade_reupload() {
+    for (policy, accepted) in [("ignore", false), ("accept", true)] {
+        let repo = Repo::new(&format!("update-downgrade-reupload-{policy}"));
+        let module = repo.module(&format!(
+            r#"{{"downgrade": "accept", "reupload": "{policy}"}}"#
+        ));
+
+        repo.publish(2, "");
+        assert!(repo.update(&module).await);
+        repo.publish(1, "");
+        assert!(repo.update(&module).await);
+        let checksum = repo.track().versions[1].checksum.to_owned();
+
+        repo.publish(2, "updateJson=changed\n");
+        assert_eq!(repo.update(&module).await, accepted, "{policy}");
+
+        let track = repo.track();
+        let v = track.versions.iter().find(|v| v.version_code == 2).unwrap();
+        assert_eq!(v.checksum != checksum, accepted, "{policy}");
+        if accepted {
+            assert_eq!(codes(&track), vec![2, 1]);
+        } else {
+            assert_eq!(codes(&track), vec![1, 2], "{policy}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn pin() {
+    let repo = Repo::new("update-pin");
+    let module = repo.module(r#"{"keep_size": 1, "pin_version_code": 1}"#);
+
+    for version_code in 1..=3 {
+        repo.publish(version_code, "");
+        assert!(repo.update(&module).await);
+    }
+
+    let track = repo.track();
+    assert_eq!(codes(&track), vec![3, 1]);
+    assert!(exists(&repo, "1.zip"));
+
+    let repository = Repository::new("Test", None, RepositorySetting::default());
+    let upgrade = Upgrade::new(&repository, repo.dir.join("json"), repo.modules_dir());
+    let modules = upgrade.generate_modules(&[Arc::new(module)]);
+    assert_eq!(modules[0].version_code, 1);
+    assert_eq!(modules[0].name, "Test 1");
+    assert_eq!(codes_of(&modules[0]), vec![1]);
+}
+
+async fn retained(setting: &str) -> Vec<i64> {
+    let repo = Repo::new("update-retention");
+    let module = repo.module(setting);
+
+    for (version, version_code) in [("v1.0", 1), ("v1.1", 2), ("v2.0", 3)] {
+        repo.publish_version(version, version_code);
+        assert!(repo.update(&module).await);
+    }
+
+    let track = repo.track();
+    for v in &track.versions {
+        assert!(exists(&repo, &v.zip_file));
+    }
+    codes(&track)
+}
+
+#[tokio::test]
+async fn retention() {
+    assert_eq!(retained(r#"{"keep_size": 1}"#).await, vec![3]);
+    assert_eq!(
+        retained(r#"{"keep_size": 1, "retention": {"keep_major_first": true}}"#).await,
+        vec![3, 1]
+    );
+    assert_eq!(
+        retained(r#"{"keep_size": 1, "retention": {"keep_newer_than": "30days"}}"#).await,
+        vec![3, 2, 1]
+    );
+    assert_eq!(
+        retained(r#"{"retention": {"min_keep": 2, "max_bytes": 1}}"#).await,
+        vec![3, 2]
+    );
+    assert_eq!(
+        retained(r#"{"retention": {"keep_major_first": true, "max_bytes": 1}}"#).await,
+        vec![3, 1]
+    );
+}
+
+#[tokio::test]
+async fn validate() {
+    let repo = Repo::new("update-validate");
+    repo.publish(1, "");
+
+    let write_config = |id: &str| {
+        repo.write_config(json!([
+            { "id": "test", "kind": "zip-url", "provider": format!("{}/test.zip", repo.url) },
+            { "id": id, "kind": "zip-url", "provider": format!("{}/{id}.zip", repo.url) }
+        ]));
+    };
+    let track_json = repo.module_dir().join(constant::TRACK_JSON);
+
+    write_config("Bad Id");
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    context.update().update_all(&[]).await;
+    assert!(!track_json.exists());
+
+    write_config("other");
+    let context = ContextWrapper::from_working_dir(&repo.dir).unwrap();
+    context.update().update_all(&[]).await;
+    assert!(track_json.exists());
+}
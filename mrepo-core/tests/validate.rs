@@ -0,0 +1,98 @@
+mod common;
+
+use std::fs;
+
+use mrepo_core::Validate;
+
+fn validate(config: &str) -> Vec<(usize, String, String)> {
+    let dir = common::temp_dir("validate");
+    let path = dir.join("config.json");
+    fs::write(&path, config).unwrap();
+
+    Validate::new(&path)
+        .validate()
+        .unwrap()
+        .into_iter()
+        .map(|d| (d.line, d.pointer, d.message))
+        .collect()
+}
+
+#[test]
+fn retention() {
+    let diagnostics = validate(
+        r#"{
+  "version": 1,
+  "log": { "disabled": true, "level": "info", "timestamp": false },
+  "repository": {
+    "name": "Test",
+    "setting": {
+      "retention": { "keep_newer_than": "30 parsecs" }
+    }
+  },
+  "modules": [
+    {
+      "id": "test",
+      "kind": "zip-url",
+      "provider": "https://repo.test.app/test.zip",
+      "setting": {
+        "retention": { "keep_newer_than": "2weeks" }
+      }
+    }
+  ]
+}"#,
+    );
+
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let (line, pointer, message) = &diagnostics[0];
+    assert_eq!(*line, 7);
+    assert_eq!(pointer, "/repository/setting/retention/keep_newer_than");
+    assert!(message.contains("`30 parsecs`"));
+}
+
+#[test]
+fn diagnostics() {
+    let diagnostics = validate(
+        r#"{
+  "version": 1,
+  "log": { "disabled": true, "level": "info", "timestamp": false },
+  "repository": {
+    "name": "Test",
+    "setting": { "base_url": "https://repo.test.app/", "keep_size": 0 }
+  },
+  "modules": [
+    { "id": "Bad Id", "kind": "zip-url", "provider": "not a url" },
+    { "id": "test", "kind": "update-json", "provider": "" }
+  ]
+}"#,
+    );
+
+    let pointers: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|(line, pointer, _)| (*line, pointer.as_str()))
+        .collect();
+    assert_eq!(
+        pointers,
+        vec![
+            (6, "/repository/setting/base_url"),
+            (6, "/repository/setting/keep_size"),
+            (9, "/modules/0/id"),
+            (9, "/modules/0/provider"),
+            (10, "/modules/1/provider"),
+        ]
+    );
+    assert!(diagnostics[2]
+        .2
+        .contains("`Bad Id` is not a valid module id"));
+    assert!(diagnostics[3].2.contains("invalid url `not a url`"));
+    assert_eq!(diagnostics[4].2, "provider must not be empty");
+}
+
+#[test]
+fn syntax_error() {
+    let diagnostics = validate("{\n  \"version\": 1,\n  \"log\": \n");
+
+    assert_eq!(diagnostics.len(), 1);
+    let (line, pointer, _) = &diagnostics[0];
+    assert_eq!(*line, 4);
+    assert!(pointer.is_empty());
+}
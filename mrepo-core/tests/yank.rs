@@ -0,0 +1,72 @@
+mod common;
+
+use std::sync::Arc;
+
+use mrepo_core::model::config::{Repository, RepositorySetting};
+use mrepo_core::{constant, Upgrade, Yank};
+
+use common::{codes, Repo};
+
+fn yanked(repo: &Repo) -> Vec<(i64, bool, String)> {
+    repo.track()
+        .versions
+        .into_iter()
+        .map(|v| (v.version_code, v.yanked, v.yanked_reason))
+        .collect()
+}
+
+#[tokio::test]
+async fn yank_unyank() {
+    let repo = Repo::new("yank");
+    let module = repo.module("{}");
+    for version_code in 1..=2 {
+        repo.publish(version_code, "");
+        assert!(repo.update(&module).await);
+    }
+
+    let yank = Yank::new(repo.modules_dir());
+    assert!(yank.yank("test", 1, "Bootloops"));
+    assert_eq!(
+        yanked(&repo),
+        vec![(2, false, String::new()), (1, true, "Bootloops".to_owned())]
+    );
+    assert!(repo.module_dir().join("1.zip").exists());
+
+    let repository = Repository::new("Test", None, RepositorySetting::default());
+    let upgrade = Upgrade::new(
+        &repository,
+        repo.dir.join(constant::JSON_DIR),
+        repo.modules_dir(),
+    );
+    let modules = upgrade.generate_modules(&[Arc::new(module)]);
+    let versions = &modules[0].versions;
+    assert_eq!(versions.len(), 2);
+    assert!(versions[1].yanked);
+    assert_eq!(versions[1].yanked_reason, "Bootloops");
+
+    assert!(yank.unyank("test", 1));
+    assert_eq!(
+        yanked(&repo),
+        vec![(2, false, String::new()), (1, false, String::new())]
+    );
+
+    assert!(!yank.yank("test", 3, ""));
+    assert!(!yank.yank("missing", 1, ""));
+}
+
+#[tokio::test]
+async fn keep_size() {
+    let repo = Repo::new("yank-keep-size");
+    let module = repo.module(r#"{"keep_size": 2}"#);
+    for version_code in 1..=2 {
+        repo.publish(version_code, "");
+        assert!(repo.update(&module).await);
+    }
+
+    assert!(Yank::new(repo.modules_dir()).yank("test", 2, ""));
+    repo.publish(3, "");
+    assert!(repo.update(&module).await);
+
+    assert_eq!(codes(&repo.track()), vec![3, 2, 1]);
+    assert!(repo.module_dir().join("1.zip").exists());
+}
@@ -1,23 +1,58 @@
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::migrate;
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+#[serde(remote = "Self")]
 pub struct Config {
+    #[serde(default)]
+    pub version: u32,
     pub log: Log,
     pub repository: Repository,
-    pub modules: Vec<Module>,
+    #[serde(default, skip_serializing_if = "ModuleTemplate::is_empty")]
+    pub defaults: ModuleTemplate,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, ModuleTemplate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub modules: Vec<ModuleEntry>,
 }
 
 impl Config {
-    pub fn new(log: Log, repository: Repository, modules: Vec<Module>) -> Self {
+    pub fn new(log: Log, repository: Repository, modules: Vec<ModuleEntry>) -> Self {
         Self {
+            version: migrate::CONFIG_VERSION,
             log,
             repository,
+            defaults: ModuleTemplate::default(),
+            templates: BTreeMap::new(),
+            include: Vec::new(),
             modules,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        migrate::config(&mut value).map_err(de::Error::custom)?;
+        Config::deserialize(value).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Config::serialize(self, serializer)
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 #[serde(default)]
 pub struct Log {
     pub disabled: bool,
@@ -49,7 +84,7 @@ impl Log {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Repository {
     pub name: String,
     #[serde(default)]
@@ -71,7 +106,7 @@ impl Repository {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Clone)]
 #[serde(default)]
 pub struct RepositoryMetadata {
     #[serde(skip_serializing_if = "String::is_empty")]
@@ -92,11 +127,13 @@ impl RepositoryMetadata {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 #[serde(default)]
 pub struct RepositorySetting {
     pub base_url: String,
     pub keep_size: usize,
+    #[serde(skip_serializing_if = "Retention::is_empty")]
+    pub retention: Retention,
 }
 
 impl Default for RepositorySetting {
@@ -104,6 +141,7 @@ impl Default for RepositorySetting {
         Self {
             base_url: String::new(),
             keep_size: 3,
+            retention: Retention::default(),
         }
     }
 }
@@ -113,11 +151,31 @@ impl RepositorySetting {
         Self {
             base_url: base_url.into(),
             keep_size,
+            retention: Retention::default(),
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(default)]
+pub struct Retention {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_keep: Option<usize>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub keep_newer_than: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub keep_major_first: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+impl Retention {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Module {
     pub id: String,
     pub kind: ProviderKind,
@@ -150,18 +208,101 @@ impl Module {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct ModuleEntry {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub template: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProviderKind>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub changelog: String,
+    #[serde(default)]
+    pub metadata: ModuleMetadata,
+    #[serde(default)]
+    pub setting: ModuleSetting,
+}
+
+impl From<Module> for ModuleEntry {
+    fn from(module: Module) -> Self {
+        Self {
+            id: module.id,
+            template: String::new(),
+            kind: Some(module.kind),
+            provider: module.provider,
+            changelog: module.changelog,
+            metadata: module.metadata,
+            setting: module.setting,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(default)]
+pub struct ModuleTemplate {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProviderKind>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub provider: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub changelog: String,
+    #[serde(skip_serializing_if = "ModuleMetadata::is_empty")]
+    pub metadata: ModuleMetadata,
+    #[serde(skip_serializing_if = "ModuleSetting::is_empty")]
+    pub setting: ModuleSetting,
+}
+
+impl ModuleTemplate {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Copy, Clone)]
 pub enum ProviderKind {
     #[serde(rename = "update-json")]
     UpdateJson,
     #[serde(rename = "zip-url")]
     ZipUrl,
+    #[serde(rename = "mirror")]
+    Mirror,
     #[cfg(feature = "git")]
     #[serde(rename = "git")]
     Git,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default, Eq, PartialEq, Clone)]
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "update-json" => Ok(ProviderKind::UpdateJson),
+            "zip-url" => Ok(ProviderKind::ZipUrl),
+            "mirror" => Ok(ProviderKind::Mirror),
+            #[cfg(feature = "git")]
+            "git" => Ok(ProviderKind::Git),
+            _ => Err(format!("unknown provider kind `{s}`")),
+        }
+    }
+}
+
+impl ProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::UpdateJson => "update-json",
+            ProviderKind::ZipUrl => "zip-url",
+            ProviderKind::Mirror => "mirror",
+            #[cfg(feature = "git")]
+            ProviderKind::Git => "git",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Clone)]
 #[serde(default)]
 pub struct ModuleMetadata {
     #[serde(skip_serializing_if = "String::is_empty")]
@@ -186,21 +327,136 @@ impl ModuleMetadata {
             source: source.into(),
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default, Eq, PartialEq, Copy, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Clone)]
 #[serde(default)]
 pub struct ModuleSetting {
-    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention: Option<Retention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downgrade: Option<VersionPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reupload: Option<VersionPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_version_code: Option<i64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub root: BTreeMap<RootSolution, bool>,
 }
 
 impl ModuleSetting {
     pub fn new(disabled: bool, keep_size: usize) -> Self {
         Self {
-            disabled,
+            disabled: Some(disabled),
             keep_size: Some(keep_size),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.unwrap_or(false)
+    }
+
+    pub fn downgrade_policy(&self) -> VersionPolicy {
+        self.downgrade.unwrap_or_default()
+    }
+
+    pub fn reupload_policy(&self) -> VersionPolicy {
+        self.reupload.unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum VersionPolicy {
+    #[default]
+    #[serde(rename = "ignore")]
+    Ignore,
+    #[serde(rename = "warn")]
+    Warn,
+    #[serde(rename = "accept")]
+    Accept,
+}
+
+impl VersionPolicy {
+    pub fn is_ignore(&self) -> bool {
+        *self == Self::Ignore
+    }
+}
+
+#[derive(
+    Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone,
+)]
+pub enum RootSolution {
+    #[serde(rename = "magisk")]
+    Magisk,
+    #[serde(rename = "kernelsu")]
+    KernelSU,
+    #[serde(rename = "apatch")]
+    APatch,
+}
+
+impl RootSolution {
+    pub const ALL: [RootSolution; 3] = [Self::Magisk, Self::KernelSU, Self::APatch];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RootSolution::Magisk => "magisk",
+            RootSolution::KernelSU => "kernelsu",
+            RootSolution::APatch => "apatch",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(default)]
+pub struct RootSupport {
+    pub magisk: bool,
+    pub kernelsu: bool,
+    pub apatch: bool,
+}
+
+impl RootSupport {
+    pub fn get(&self, solution: RootSolution) -> bool {
+        match solution {
+            RootSolution::Magisk => self.magisk,
+            RootSolution::KernelSU => self.kernelsu,
+            RootSolution::APatch => self.apatch,
+        }
+    }
+
+    pub fn set(&mut self, solution: RootSolution, supported: bool) {
+        match solution {
+            RootSolution::Magisk => self.magisk = supported,
+            RootSolution::KernelSU => self.kernelsu = supported,
+            RootSolution::APatch => self.apatch = supported,
+        }
+    }
+
+    pub fn apply(&mut self, overrides: &BTreeMap<RootSolution, bool>) {
+        for (solution, supported) in overrides {
+            self.set(*solution, *supported);
+        }
+    }
+}
+
+impl Default for RootSupport {
+    fn default() -> Self {
+        Self {
+            magisk: true,
+            kernelsu: true,
+            apatch: true,
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod config;
+pub mod migrate;
 pub mod modules;
 pub mod origin;
+pub mod schema;
 pub mod track;
@@ -0,0 +1,66 @@
+use serde_json::Value;
+
+type Step = fn(&mut Value);
+
+const CONFIG_STEPS: [Step; 1] = [config_v1];
+const TRACK_STEPS: [Step; 1] = [track_v1];
+
+pub const CONFIG_VERSION: u32 = CONFIG_STEPS.len() as u32;
+pub const TRACK_VERSION: u32 = TRACK_STEPS.len() as u32;
+
+fn migrate(value: &mut Value, steps: &[Step]) -> Result<bool, String> {
+    let object = value.as_object().ok_or("expected an object")?;
+    let version = match object.get("version") {
+        Some(v) => v.as_u64().ok_or("version must be a non-negative integer")? as usize,
+        None => 0,
+    };
+
+    if version > steps.len() {
+        return Err(format!(
+            "version {version} is newer than the supported version {}",
+            steps.len()
+        ));
+    }
+
+    for step in &steps[version..] {
+        step(value);
+    }
+
+    value["version"] = Value::from(steps.len());
+    Ok(version < steps.len())
+}
+
+pub fn config(value: &mut Value) -> Result<bool, String> {
+    migrate(value, &CONFIG_STEPS)
+}
+
+pub fn track(value: &mut Value) -> Result<bool, String> {
+    migrate(value, &TRACK_STEPS)
+}
+
+fn config_v1(_: &mut Value) {}
+
+fn file_name(url: &str) -> String {
+    url.rsplit('/').next().unwrap_or_default().to_owned()
+}
+
+fn track_v1(value: &mut Value) {
+    let versions = match value.get_mut("versions").and_then(Value::as_array_mut) {
+        Some(v) => v,
+        None => return,
+    };
+
+    for version in versions.iter_mut().filter_map(Value::as_object_mut) {
+        for key in ["zip_url", "zipUrl"] {
+            if let Some(Value::String(url)) = version.remove(key) {
+                version
+                    .entry("zip_file")
+                    .or_insert_with(|| Value::from(file_name(&url)));
+            }
+        }
+
+        if let Some(Value::String(changelog)) = version.get_mut("changelog") {
+            *changelog = file_name(changelog);
+        }
+    }
+}
@@ -1,33 +1,68 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{config, origin};
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Modules {
     pub name: String,
+    #[serde(default)]
     pub timestamp: i64,
+    #[serde(default)]
     pub metadata: config::RepositoryMetadata,
     pub modules: Vec<Module>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Module {
     pub id: String,
     pub name: String,
     pub version: String,
+    #[serde(alias = "versionCode")]
     pub version_code: i64,
     pub author: String,
     pub description: String,
+    #[serde(default)]
     pub metadata: config::ModuleMetadata,
+    #[serde(default, alias = "minApi", skip_serializing_if = "Option::is_none")]
+    pub min_api: Option<i64>,
+    #[serde(default, alias = "maxApi", skip_serializing_if = "Option::is_none")]
+    pub max_api: Option<i64>,
+    #[serde(default, alias = "minMagisk", skip_serializing_if = "Option::is_none")]
+    pub min_magisk: Option<i64>,
+    #[serde(
+        default,
+        alias = "needRamdisk",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub need_ramdisk: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<config::RootSupport>,
     pub versions: Vec<Version>,
 }
 
 impl Module {
     pub fn build(
         origin: origin::Module,
-        metadata: config::ModuleMetadata,
+        mut metadata: config::ModuleMetadata,
+        root: Option<config::RootSupport>,
         versions: Vec<Version>,
     ) -> Self {
+        let number = |key: &str| origin.get_prop(key).and_then(|v| v.parse().ok());
+        let min_api = number("minApi");
+        let max_api = number("maxApi");
+        let min_magisk = number("minMagisk");
+        let need_ramdisk = origin.get_prop("needRamdisk") == Some("true");
+
+        for (field, key) in [
+            (&mut metadata.support, "support"),
+            (&mut metadata.donate, "donate"),
+        ] {
+            if field.is_empty() {
+                *field = origin.get_prop(key).unwrap_or_default().to_owned();
+            }
+        }
+
         Self {
             id: origin.id,
             name: origin.name,
@@ -36,16 +71,28 @@ impl Module {
             author: origin.author,
             description: origin.description,
             metadata,
+            min_api,
+            max_api,
+            min_magisk,
+            need_ramdisk,
+            root,
             versions,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Version {
     pub timestamp: i64,
     pub version: String,
+    #[serde(alias = "versionCode")]
     pub version_code: i64,
+    #[serde(alias = "zipUrl")]
     pub zip_url: String,
+    #[serde(default)]
     pub changelog: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub yanked: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub yanked_reason: String,
 }
@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct UpdateJson {
     pub version: String,
     #[cfg(not(feature = "raw"))]
@@ -18,7 +21,7 @@ pub struct UpdateJson {
     pub changelog: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Module {
     pub id: String,
     pub name: String,
@@ -31,4 +34,52 @@ pub struct Module {
     pub version_code: i64,
     pub author: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prop: BTreeMap<String, String>,
+}
+
+impl Module {
+    pub fn get_prop(&self, key: &str) -> Option<&str> {
+        self.prop
+            .get(key)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct GithubRelease {
+    pub tag_name: String,
+    #[serde(default)]
+    pub published_at: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct GithubCommit {
+    pub sha: String,
+    pub commit: GithubCommitDetail,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct GithubCommitDetail {
+    pub committer: GithubSignature,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+pub struct GithubSignature {
+    pub date: String,
 }
@@ -0,0 +1,15 @@
+use schemars::{schema_for, Schema};
+
+use crate::{config, modules, track};
+
+pub fn config() -> Schema {
+    schema_for!(config::Config)
+}
+
+pub fn modules() -> Schema {
+    schema_for!(modules::Modules)
+}
+
+pub fn track() -> Schema {
+    schema_for!(track::Track)
+}
@@ -1,20 +1,57 @@
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
-use crate::origin;
+use crate::{migrate, origin};
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
+#[serde(remote = "Self")]
 pub struct Track {
+    #[serde(default)]
+    pub version: u32,
     pub module: origin::Module,
     pub versions: Vec<Version>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+impl Track {
+    pub fn new(module: origin::Module, versions: Vec<Version>) -> Self {
+        Self {
+            version: migrate::TRACK_VERSION,
+            module,
+            versions,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Track {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        migrate::track(&mut value).map_err(de::Error::custom)?;
+        Track::deserialize(value).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Track {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Track::serialize(self, serializer)
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Eq, PartialEq, Clone)]
 pub struct Version {
     pub timestamp: i64,
     pub version: String,
     pub version_code: i64,
     pub zip_file: String,
     pub changelog: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub checksum: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub withdrawn: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub yanked: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub yanked_reason: String,
 }
 
 impl Version {
@@ -25,6 +62,10 @@ impl Version {
             version_code,
             zip_file: format!("{version_code}.zip"),
             changelog: format!("{version_code}.md"),
+            checksum: String::new(),
+            withdrawn: false,
+            yanked: false,
+            yanked_reason: String::new(),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use mrepo_model::config::*;
 
 #[test]
@@ -24,6 +26,101 @@ fn log() {
     );
 }
 
+#[test]
+fn config() {
+    let json = r#"
+    {
+      "log": {},
+      "repository": {
+        "name": "Test Repo",
+        "setting": {
+          "base_url": "https://repo.test.app"
+        }
+      },
+      "include": ["modules.d", "extra/*.toml"]
+    }"#;
+    let config = serde_json::from_str::<Config>(json).unwrap();
+    assert_eq!(config.include, vec!["modules.d", "extra/*.toml"]);
+    assert!(config.modules.is_empty());
+
+    let config = Config {
+        include: Vec::new(),
+        ..config
+    };
+    assert!(!serde_json::to_string(&config).unwrap().contains("include"));
+}
+
+#[test]
+fn templates() {
+    let json = r#"
+    {
+      "log": {},
+      "repository": {
+        "name": "Test Repo",
+        "setting": {
+          "base_url": "https://repo.test.app"
+        }
+      },
+      "defaults": {
+        "vars": {
+          "github": "https://github.com/test"
+        },
+        "metadata": {
+          "donate": "https://repo.test.app/donate"
+        },
+        "setting": {
+          "keep_size": 5
+        }
+      },
+      "templates": {
+        "release": {
+          "kind": "zip-url",
+          "provider": "{github}/{id}/releases"
+        }
+      },
+      "modules": [
+        {
+          "id": "test",
+          "template": "release"
+        }
+      ]
+    }"#;
+    let config = serde_json::from_str::<Config>(json).unwrap();
+    assert_eq!(config.defaults.vars["github"], "https://github.com/test");
+    assert_eq!(config.defaults.setting.keep_size, Some(5));
+    assert_eq!(config.defaults.kind, None);
+
+    let release = &config.templates["release"];
+    assert_eq!(release.kind, Some(ProviderKind::ZipUrl));
+    assert_eq!(release.provider, "{github}/{id}/releases");
+
+    let module = &config.modules[0];
+    assert_eq!(module.template, "release");
+    assert_eq!(module.kind, None);
+    assert!(module.provider.is_empty());
+
+    let config = Config::new(config.log, config.repository, Vec::new());
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(!json.contains("defaults"));
+    assert!(!json.contains("templates"));
+}
+
+#[test]
+fn module_entry() {
+    let module = Module::new(
+        "test",
+        ProviderKind::UpdateJson,
+        "https://repo.test.app/update.json",
+        "",
+        None,
+        None,
+    );
+    let entry = ModuleEntry::from(module);
+    assert_eq!(entry.kind, Some(ProviderKind::UpdateJson));
+    assert!(entry.template.is_empty());
+    assert!(!serde_json::to_string(&entry).unwrap().contains("template"));
+}
+
 #[test]
 fn repository() {
     let json = r#"
@@ -87,6 +184,37 @@ fn repository() {
             RepositorySetting::new("https://repo.test.app", 10)
         )
     );
+
+    let json = r#"
+    {
+      "name": "Test Repo",
+      "setting": {
+        "base_url": "https://repo.test.app",
+        "keep_size": 10,
+        "retention": {
+          "min_keep": 2,
+          "keep_newer_than": "30d",
+          "keep_major_first": true,
+          "max_bytes": 1073741824
+        }
+      }
+    }"#;
+    assert_eq!(
+        serde_json::from_str::<Repository>(json).unwrap(),
+        Repository::new(
+            "Test Repo",
+            None,
+            RepositorySetting {
+                retention: Retention {
+                    min_keep: Some(2),
+                    keep_newer_than: "30d".to_owned(),
+                    keep_major_first: true,
+                    max_bytes: Some(1073741824),
+                },
+                ..RepositorySetting::new("https://repo.test.app", 10)
+            }
+        )
+    );
 }
 
 #[test]
@@ -167,7 +295,99 @@ fn module() {
             "https://test.app/test.zip",
             "https://test.app/changelog.md",
             ModuleMetadata::new("MIT", "https://test.app/", "", "", ""),
-            ModuleSetting::new(false, 10)
+            ModuleSetting {
+                keep_size: Some(10),
+                ..Default::default()
+            }
         )
     );
+    let json = r#"
+    {
+      "id": "test",
+      "kind": "zip-url",
+      "provider": "https://test.app/test.zip",
+      "setting": {
+        "downgrade": "warn",
+        "reupload": "accept"
+      }
+    }"#;
+    assert_eq!(
+        serde_json::from_str::<Module>(json).unwrap(),
+        Module::new(
+            "test",
+            ProviderKind::ZipUrl,
+            "https://test.app/test.zip",
+            "",
+            None,
+            ModuleSetting {
+                downgrade: Some(VersionPolicy::Warn),
+                reupload: Some(VersionPolicy::Accept),
+                ..Default::default()
+            }
+        )
+    );
+    let json = r#"
+    {
+      "id": "test",
+      "kind": "update-json",
+      "provider": "https://test.app/update.json",
+      "setting": {
+        "keep_size": 3,
+        "pin_version_code": 7023
+      }
+    }"#;
+    assert_eq!(
+        serde_json::from_str::<Module>(json).unwrap(),
+        Module::new(
+            "test",
+            ProviderKind::UpdateJson,
+            "https://test.app/update.json",
+            "",
+            None,
+            ModuleSetting {
+                keep_size: Some(3),
+                pin_version_code: Some(7023),
+                ..Default::default()
+            }
+        )
+    );
+}
+
+#[test]
+fn provider_kind() {
+    for kind in [ProviderKind::UpdateJson, ProviderKind::ZipUrl] {
+        assert_eq!(kind.as_str().parse::<ProviderKind>(), Ok(kind));
+    }
+    assert!("zip".parse::<ProviderKind>().is_err());
+}
+
+#[test]
+fn root_support() {
+    let json = r#"
+    {
+      "root": {
+        "magisk": false,
+        "apatch": true
+      }
+    }"#;
+    let setting = serde_json::from_str::<ModuleSetting>(json).unwrap();
+    assert_eq!(
+        setting.root,
+        BTreeMap::from([(RootSolution::Magisk, false), (RootSolution::APatch, true)])
+    );
+
+    let mut support = serde_json::from_str::<RootSupport>(r#"{"kernelsu": false}"#).unwrap();
+    assert!(support.magisk && !support.kernelsu && support.apatch);
+    support.apply(&setting.root);
+    assert_eq!(
+        support,
+        RootSupport {
+            magisk: false,
+            kernelsu: false,
+            apatch: true
+        }
+    );
+    assert!(!serde_json::to_string(&ModuleSetting::default())
+        .unwrap()
+        .contains("root"));
 }
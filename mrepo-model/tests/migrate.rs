@@ -0,0 +1,61 @@
+use serde_json::json;
+
+use mrepo_model::config::Config;
+use mrepo_model::migrate;
+use mrepo_model::track::Track;
+
+#[test]
+fn config() {
+    let mut value = json!({
+      "log": {},
+      "repository": {
+        "name": "Test Repo",
+        "setting": {
+          "base_url": "https://repo.test.app"
+        }
+      }
+    });
+    assert!(migrate::config(&mut value.clone()).unwrap());
+
+    let config: Config = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(config.version, migrate::CONFIG_VERSION);
+
+    assert!(migrate::config(&mut value).unwrap());
+    assert!(!migrate::config(&mut value).unwrap());
+
+    value["version"] = json!(migrate::CONFIG_VERSION + 1);
+    assert!(migrate::config(&mut value).is_err());
+    assert!(serde_json::from_value::<Config>(value).is_err());
+}
+
+#[test]
+fn track() {
+    let value = json!({
+      "module": {
+        "id": "test",
+        "name": "Test",
+        "version": "v1.0",
+        "versionCode": 100,
+        "author": "Sanmer",
+        "description": "Test module"
+      },
+      "versions": [
+        {
+          "timestamp": 1697034252000i64,
+          "version": "v1.0",
+          "version_code": 100,
+          "zip_url": "https://repo.test.app/modules/test/100.zip",
+          "changelog": "https://repo.test.app/modules/test/100.md"
+        }
+      ]
+    });
+
+    let track: Track = serde_json::from_value(value).unwrap();
+    assert_eq!(track.version, migrate::TRACK_VERSION);
+    assert_eq!(track.versions[0].zip_file, "100.zip");
+    assert_eq!(track.versions[0].changelog, "100.md");
+
+    let json = serde_json::to_value(&track).unwrap();
+    assert_eq!(json["version"], json!(migrate::TRACK_VERSION));
+    assert!(json["versions"][0].get("zip_url").is_none());
+}
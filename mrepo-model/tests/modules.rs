@@ -0,0 +1,96 @@
+use mrepo_model::modules::*;
+
+#[test]
+fn modules() {
+    let json = r#"
+    {
+      "name": "Test Repo",
+      "modules": [
+        {
+          "id": "test",
+          "name": "Test",
+          "version": "v1.0",
+          "versionCode": 100,
+          "author": "Sanmer",
+          "description": "Test module",
+          "versions": [
+            {
+              "timestamp": 1697034252000,
+              "version": "v1.0",
+              "versionCode": 100,
+              "zipUrl": "https://repo.test.app/modules/test/100.zip"
+            }
+          ]
+        }
+      ]
+    }"#;
+    let modules = serde_json::from_str::<Modules>(json).unwrap();
+    assert_eq!(modules.timestamp, 0);
+
+    let module = &modules.modules[0];
+    assert_eq!(module.version_code, 100);
+    assert_eq!(module.versions[0].version_code, 100);
+    assert_eq!(
+        module.versions[0].zip_url,
+        "https://repo.test.app/modules/test/100.zip"
+    );
+    assert!(module.versions[0].changelog.is_empty());
+
+    let json = serde_json::to_string(module).unwrap();
+    assert!(json.contains("\"version_code\""));
+    assert!(!json.contains("versionCode"));
+}
+
+#[test]
+fn build() {
+    let json = r#"
+    {
+      "id": "test",
+      "name": "Test",
+      "version": "v1.0",
+      "versionCode": 100,
+      "author": "Sanmer",
+      "description": "Test module",
+      "prop": {
+        "minApi": "29",
+        "maxApi": " ",
+        "minMagisk": "26000",
+        "needRamdisk": "true",
+        "support": "https://support.test.app",
+        "updateJson": "https://repo.test.app/test.json"
+      }
+    }"#;
+    let origin = serde_json::from_str::<mrepo_model::origin::Module>(json).unwrap();
+    assert_eq!(
+        origin.get_prop("updateJson"),
+        Some("https://repo.test.app/test.json")
+    );
+    assert_eq!(origin.get_prop("maxApi"), None);
+
+    let metadata = mrepo_model::config::ModuleMetadata {
+        donate: "https://donate.test.app".to_owned(),
+        ..Default::default()
+    };
+    let module = Module::build(origin, metadata, Default::default(), Vec::new());
+    assert_eq!(module.min_api, Some(29));
+    assert_eq!(module.max_api, None);
+    assert_eq!(module.min_magisk, Some(26000));
+    assert!(module.need_ramdisk);
+    assert_eq!(module.metadata.support, "https://support.test.app");
+    assert_eq!(module.metadata.donate, "https://donate.test.app");
+
+    let json = serde_json::to_string(&module).unwrap();
+    assert!(json.contains("\"min_api\":29"));
+    assert!(!json.contains("max_api"));
+    assert!(!json.contains("root"));
+
+    let module = Module {
+        root: Some(mrepo_model::config::RootSupport {
+            magisk: false,
+            ..Default::default()
+        }),
+        ..module
+    };
+    let json = serde_json::to_string(&module).unwrap();
+    assert!(json.contains(r#""root":{"magisk":false,"kernelsu":true,"apatch":true}"#));
+}
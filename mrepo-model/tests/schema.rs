@@ -0,0 +1,31 @@
+use mrepo_model::schema;
+
+#[test]
+fn config() {
+    let schema = schema::config();
+    let module = schema.pointer("/$defs/ModuleEntry").unwrap();
+    let required = module.pointer("/required").unwrap().as_array().unwrap();
+    assert!(required.contains(&"id".into()));
+    assert!(!required.contains(&"provider".into()));
+    assert!(schema.pointer("/properties/templates").is_some());
+
+    let kind = schema.pointer("/$defs/ProviderKind").unwrap().to_string();
+    assert!(kind.contains("update-json"));
+    assert!(kind.contains("zip-url"));
+}
+
+#[test]
+fn track() {
+    let schema = schema::track();
+    let module = schema.pointer("/$defs/Module/properties").unwrap();
+    #[cfg(not(feature = "raw"))]
+    assert!(module.get("version_code").is_some());
+    #[cfg(feature = "raw")]
+    assert!(module.get("versionCode").is_some());
+}
+
+#[test]
+fn modules() {
+    let schema = schema::modules();
+    assert!(schema.pointer("/properties/modules").is_some());
+}
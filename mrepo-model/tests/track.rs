@@ -0,0 +1,50 @@
+use mrepo_model::track::*;
+
+#[test]
+fn version() {
+    let json = r#"
+    {
+      "timestamp": 1697034252000,
+      "version": "v1.9.2 (7024)",
+      "version_code": 7024,
+      "zip_file": "7024.zip",
+      "changelog": "7024.md"
+    }"#;
+    let version = Version::new(1697034252000, "v1.9.2 (7024)".to_owned(), 7024);
+    assert_eq!(serde_json::from_str::<Version>(json).unwrap(), version);
+    assert!(!serde_json::to_string(&version).unwrap().contains("withdrawn"));
+
+    let json = r#"
+    {
+      "timestamp": 1697034252000,
+      "version": "v1.9.2 (7024)",
+      "version_code": 7024,
+      "zip_file": "7024.zip",
+      "changelog": "7024.md",
+      "checksum": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+      "withdrawn": true
+    }"#;
+    let version = Version {
+        checksum: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_owned(),
+        withdrawn: true,
+        ..version
+    };
+    assert_eq!(serde_json::from_str::<Version>(json).unwrap(), version);
+
+    let json = r#"
+    {
+      "timestamp": 1697034252000,
+      "version": "v1.9.2 (7024)",
+      "version_code": 7024,
+      "zip_file": "7024.zip",
+      "changelog": "7024.md",
+      "yanked": true,
+      "yanked_reason": "Bootloop on Android 14"
+    }"#;
+    let version = Version {
+        yanked: true,
+        yanked_reason: "Bootloop on Android 14".to_owned(),
+        ..Version::new(1697034252000, "v1.9.2 (7024)".to_owned(), 7024)
+    };
+    assert_eq!(serde_json::from_str::<Version>(json).unwrap(), version);
+}